@@ -18,16 +18,38 @@ ACTIVATE_SCRIPT="$APP_DIR/Resources/activate.sh"
 
 LOG="/tmp/lovnotifier-send.log"
 
+# screen exports the current window as $WINDOW, which the option below reuses
+SCREEN_WINDOW="${WINDOW:-}"
+
 TITLE=""
 MESSAGE=""
 SESSION=""
 WINDOW=""
 PANE=""
+# Inside tmux, default to the stable pane id and the server socket ($TMUX is "socket,pid,session")
+PANE_ID="${TMUX_PANE:-}"
+SOCKET="${TMUX%%,*}"
+HOST="$(hostname 2>/dev/null)"
+SSH_TARGET=""
+# Outside tmux, pick up Zellij or screen from their environment
+MUX=""
+if [ -z "$TMUX" ] && [ -n "$ZELLIJ" ]; then
+    MUX="zellij"
+    SESSION="${ZELLIJ_SESSION_NAME:-}"
+    PANE="${ZELLIJ_PANE_ID:-}"
+elif [ -z "$TMUX" ] && [ -n "$STY" ]; then
+    MUX="screen"
+    SESSION="$STY"
+    WINDOW="$SCREEN_WINDOW"
+fi
 GROUP=""
 SOUND=""
 PROJECT=""
 PROJECT_PATH=""
 SESSION_ID=""
+SOURCE=""
+LINK=""
+PRIORITY=""
 USE_SYSTEM_NOTIFY=""
 
 echo "[SEND] ===== $(date) =====" >> "$LOG"
@@ -41,11 +63,19 @@ while [[ $# -gt 0 ]]; do
         -session) SESSION="$2"; shift 2 ;;
         -window) WINDOW="$2"; shift 2 ;;
         -pane) PANE="$2"; shift 2 ;;
+        -pane-id) PANE_ID="$2"; shift 2 ;;
+        -socket) SOCKET="$2"; shift 2 ;;
+        -host) HOST="$2"; shift 2 ;;
+        -mux) MUX="$2"; shift 2 ;;
+        -ssh-target) SSH_TARGET="$2"; shift 2 ;;
         -group) GROUP="$2"; shift 2 ;;
         -sound) SOUND="$2"; shift 2 ;;
         -project) PROJECT="$2"; shift 2 ;;
         -project-path) PROJECT_PATH="$2"; shift 2 ;;
         -session-id) SESSION_ID="$2"; shift 2 ;;
+        -source) SOURCE="$2"; shift 2 ;;
+        -link) LINK="$2"; shift 2 ;;
+        -priority) PRIORITY="$2"; shift 2 ;;
         -system-notify) USE_SYSTEM_NOTIFY="true"; shift ;;
         *) shift ;;
     esac
@@ -61,9 +91,18 @@ build_json() {
     [ -n "$PROJECT" ] && json+=",\"project\":\"$PROJECT\""
     [ -n "$PROJECT_PATH" ] && json+=",\"project_path\":\"$PROJECT_PATH\""
     [ -n "$SESSION_ID" ] && json+=",\"session_id\":\"$SESSION_ID\""
+    [ -n "$MUX" ] && json+=",\"mux_kind\":\"$MUX\""
     [ -n "$SESSION" ] && json+=",\"tmux_session\":\"$SESSION\""
     [ -n "$WINDOW" ] && json+=",\"tmux_window\":\"$WINDOW\""
     [ -n "$PANE" ] && json+=",\"tmux_pane\":\"$PANE\""
+    [ -n "$PANE_ID" ] && json+=",\"tmux_pane_id\":\"$PANE_ID\""
+    [ -n "$SOCKET" ] && json+=",\"tmux_socket\":\"$SOCKET\""
+    [ -n "$HOST" ] && json+=",\"host\":\"$HOST\""
+    [ -n "$SSH_TARGET" ] && json+=",\"ssh_target\":\"$SSH_TARGET\""
+    [ -n "$SOURCE" ] && json+=",\"source\":\"$SOURCE\""
+    [ -n "$LINK" ] && json+=",\"link\":\"$LINK\""
+    [ -n "$PRIORITY" ] && json+=",\"priority\":\"$PRIORITY\""
+    [ -n "$SOUND" ] && json+=",\"sound\":\"$SOUND\""
 
     json+="}"
     echo "$json"
@@ -89,7 +128,7 @@ send_http() {
     return $exit_code
 }
 
-# 发送系统通知（可选）
+# 发送系统通知（可选，旧方式：应用已自行弹出原生通知，-sound 会随 HTTP 请求发送）
 send_system_notify() {
     if [ ! -x "$NOTIFIER_BIN" ]; then
         echo "[SEND] warning: terminal-notifier not found at $NOTIFIER_BIN" >> "$LOG"
@@ -0,0 +1,166 @@
+use crate::{Priority, ReviewItem};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::sync::{LazyLock, Mutex};
+use tauri::{AppHandle, Emitter, Runtime};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct DndSettings {
+    /// Manual do-not-disturb, toggled from the tray or the `toggle_dnd`
+    /// shortcut
+    pub enabled: bool,
+    /// Urgent items still raise a native notification during DND
+    pub urgent_breaks_through: bool,
+    /// Weekly schedules during which DND is on
+    pub quiet_hours: Vec<QuietHours>,
+}
+
+impl Default for DndSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            urgent_breaks_through: true,
+            quiet_hours: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuietHours {
+    /// Local time as "HH:MM"; `start` after `end` wraps past midnight
+    pub start: String,
+    pub end: String,
+    /// Weekdays as "mon".."sun" (empty = every day)
+    #[serde(default)]
+    pub days: Vec<String>,
+}
+
+/// Whether DND was on at the last `refresh`
+static ACTIVE: LazyLock<Mutex<bool>> = LazyLock::new(|| Mutex::new(false));
+
+/// Quiet hours are skipped until this time after DND was turned off during them
+static QUIET_HOURS_SKIPPED_UNTIL: LazyLock<Mutex<Option<NaiveDateTime>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// Items whose notifications were held back during DND
+static HELD: LazyLock<Mutex<Vec<String>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+fn now() -> NaiveDateTime {
+    chrono::Local::now().naive_local()
+}
+
+/// Rejects quiet hours with times that do not parse
+pub fn validate(settings: &DndSettings) -> Result<(), String> {
+    for window in &settings.quiet_hours {
+        crate::rules::parse_time(&window.start)?;
+        crate::rules::parse_time(&window.end)?;
+    }
+    Ok(())
+}
+
+/// End of the quiet hours `now` falls in, if any
+fn quiet_until(settings: &DndSettings, now: &NaiveDateTime) -> Option<NaiveDateTime> {
+    settings
+        .quiet_hours
+        .iter()
+        .filter_map(|window| {
+            let start = crate::rules::parse_time(&window.start).ok()?;
+            let end = crate::rules::parse_time(&window.end).ok()?;
+            if !crate::rules::in_window(now, (start, end), &window.days) {
+                return None;
+            }
+            let until = now.date().and_time(end);
+            Some(if until > *now {
+                until
+            } else {
+                until + chrono::Duration::days(1)
+            })
+        })
+        .max()
+}
+
+fn quiet_hours_active(settings: &DndSettings, now: &NaiveDateTime) -> Option<NaiveDateTime> {
+    let until = quiet_until(settings, now)?;
+    let skipped = QUIET_HOURS_SKIPPED_UNTIL
+        .lock()
+        .unwrap()
+        .is_some_and(|skipped| *now < skipped);
+    (!skipped).then_some(until)
+}
+
+pub fn is_active(settings: &DndSettings) -> bool {
+    settings.enabled || quiet_hours_active(settings, &now()).is_some()
+}
+
+/// Tray tooltip, naming the DND state when it is on
+pub fn tooltip(settings: &DndSettings) -> String {
+    if settings.enabled {
+        "Lovnotifier · Do Not Disturb".to_string()
+    } else if let Some(until) = quiet_hours_active(settings, &now()) {
+        format!("Lovnotifier · Quiet hours until {}", until.format("%H:%M"))
+    } else {
+        "Lovnotifier".to_string()
+    }
+}
+
+/// Filters out the items whose notifications DND holds back and remembers
+/// them for the summary when it ends
+pub fn hold<'a>(settings: &DndSettings, items: Vec<&'a ReviewItem>) -> Vec<&'a ReviewItem> {
+    if !is_active(settings) {
+        return items;
+    }
+    let (shown, held): (Vec<&ReviewItem>, Vec<&ReviewItem>) = items
+        .into_iter()
+        .partition(|item| settings.urgent_breaks_through && item.priority == Priority::Urgent);
+    HELD.lock()
+        .unwrap()
+        .extend(held.iter().map(|item| item.id.clone()));
+    shown
+}
+
+/// Turns DND off if it is on, for the rest of the current quiet hours too,
+/// and on otherwise
+pub fn toggle<R: Runtime>(app: &AppHandle<R>) {
+    let mut settings = crate::current_settings();
+    if is_active(&settings.dnd) {
+        settings.dnd.enabled = false;
+        *QUIET_HOURS_SKIPPED_UNTIL.lock().unwrap() = quiet_until(&settings.dnd, &now());
+    } else {
+        settings.dnd.enabled = true;
+    }
+    if let Err(e) = crate::store_settings(app, settings) {
+        println!("[Lovnotifier] Failed to toggle do not disturb: {}", e);
+    }
+}
+
+/// Picks up DND turning on or off, from the toggle or a schedule. When it
+/// ends, the held items that are still pending are summarized.
+pub fn refresh<R: Runtime>(app: &AppHandle<R>) {
+    let settings = crate::current_settings();
+    let active = is_active(&settings.dnd);
+    let was_active = std::mem::replace(&mut *ACTIVE.lock().unwrap(), active);
+    if active == was_active {
+        return;
+    }
+
+    println!(
+        "[Lovnotifier] Do not disturb {}",
+        if active { "on" } else { "off" }
+    );
+    let _ = app.emit("dnd-changed", active);
+    crate::update_tray_menu(app);
+    if active {
+        return;
+    }
+
+    let held = std::mem::take(&mut *HELD.lock().unwrap());
+    let pending: Vec<ReviewItem> = crate::REVIEW_QUEUE
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|item| held.contains(&item.id))
+        .cloned()
+        .collect();
+    crate::notifications::notify_held(app, &pending);
+}
@@ -0,0 +1,242 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::{
+    AppHandle, Manager, Monitor, PhysicalPosition, PhysicalRect, PhysicalSize, Runtime,
+    WebviewWindow, WindowEvent,
+};
+
+/// Where a new float window opens, in logical pixels from the top left of
+/// the primary monitor's work area
+const DEFAULT_POSITION: (f64, f64) = (100.0, 100.0);
+
+/// The float window's size in logical pixels
+pub const SIZE: (f64, f64) = (121.0, 48.0);
+
+/// Moves during a drag arrive in bursts; the file is written once they settle
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// Float window position and size in physical pixels
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+struct FloatFrame {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+/// Last frame per monitor configuration, see `monitor_key`
+static FRAMES: LazyLock<Mutex<HashMap<String, FloatFrame>>> =
+    LazyLock::new(|| Mutex::new(load_frames()));
+
+/// Bumped on every change; a delayed save is skipped if a newer one follows
+static SAVE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn get_frames_path() -> PathBuf {
+    crate::get_data_dir().join("float_window.json")
+}
+
+fn load_frames() -> HashMap<String, FloatFrame> {
+    fs::read_to_string(get_frames_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_frames() {
+    let path = get_frames_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let frames = FRAMES.lock().unwrap();
+    if let Ok(json) = serde_json::to_string_pretty(&*frames) {
+        let _ = fs::write(&path, json);
+    }
+}
+
+fn schedule_save() {
+    let generation = SAVE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SAVE_DELAY).await;
+        if SAVE_GENERATION.load(Ordering::SeqCst) == generation {
+            save_frames();
+        }
+    });
+}
+
+/// Identifies the connected monitors and their arrangement, so each desk
+/// setup keeps its own position
+fn monitor_key(monitors: &[Monitor]) -> String {
+    let mut parts: Vec<String> = monitors
+        .iter()
+        .map(|monitor| {
+            format!(
+                "{}@{},{}:{}x{}",
+                monitor.name().map(String::as_str).unwrap_or("?"),
+                monitor.position().x,
+                monitor.position().y,
+                monitor.size().width,
+                monitor.size().height
+            )
+        })
+        .collect();
+    parts.sort();
+    parts.join(";")
+}
+
+fn current_frame<R: Runtime>(window: &WebviewWindow<R>) -> Option<FloatFrame> {
+    let position = window.outer_position().ok()?;
+    let size = window.inner_size().ok()?;
+    Some(FloatFrame {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    })
+}
+
+fn overlap(frame: FloatFrame, area: &PhysicalRect<i32, u32>) -> i64 {
+    let left = frame.x.max(area.position.x);
+    let right = (frame.x + frame.width as i32).min(area.position.x + area.size.width as i32);
+    let top = frame.y.max(area.position.y);
+    let bottom = (frame.y + frame.height as i32).min(area.position.y + area.size.height as i32);
+    (right - left).max(0) as i64 * (bottom - top).max(0) as i64
+}
+
+/// Fits `frame` into the work area of the monitor it overlaps most, or of
+/// the primary monitor if it is on none of them
+fn clamp_frame(
+    frame: FloatFrame,
+    monitors: &[Monitor],
+    primary: Option<&Monitor>,
+) -> Option<FloatFrame> {
+    let area = monitors
+        .iter()
+        .map(|monitor| monitor.work_area())
+        .filter(|area| overlap(frame, area) > 0)
+        .max_by_key(|area| overlap(frame, area))
+        .or_else(|| primary.map(|monitor| monitor.work_area()))
+        .or_else(|| monitors.first().map(|monitor| monitor.work_area()))?;
+
+    let width = frame.width.min(area.size.width);
+    let height = frame.height.min(area.size.height);
+    let max_x = area.position.x + (area.size.width - width) as i32;
+    let max_y = area.position.y + (area.size.height - height) as i32;
+    Some(FloatFrame {
+        x: frame.x.clamp(area.position.x, max_x),
+        y: frame.y.clamp(area.position.y, max_y),
+        width,
+        height,
+    })
+}
+
+/// The default frame on `primary`, see `DEFAULT_POSITION`
+fn default_frame(primary: &Monitor) -> FloatFrame {
+    let area = primary.work_area();
+    let scale = primary.scale_factor();
+    FloatFrame {
+        x: area.position.x + (DEFAULT_POSITION.0 * scale) as i32,
+        y: area.position.y + (DEFAULT_POSITION.1 * scale) as i32,
+        width: (SIZE.0 * scale) as u32,
+        height: (SIZE.1 * scale) as u32,
+    }
+}
+
+/// Where the float window belongs with `monitors` connected: its saved frame,
+/// or the default one, kept within the visible screen area
+fn target_frame(monitors: &[Monitor], primary: Option<&Monitor>) -> Option<FloatFrame> {
+    let saved = FRAMES.lock().unwrap().get(&monitor_key(monitors)).copied();
+    let frame = saved.or_else(|| primary.map(default_frame))?;
+    clamp_frame(frame, monitors, primary)
+}
+
+fn apply_frame<R: Runtime>(window: &WebviewWindow<R>, frame: FloatFrame) {
+    if current_frame(window) == Some(frame) {
+        return;
+    }
+    let _ = window.set_size(PhysicalSize::new(frame.width, frame.height));
+    let _ = window.set_position(PhysicalPosition::new(frame.x, frame.y));
+}
+
+/// Records the float window's frame for the current monitor configuration
+fn remember<R: Runtime>(window: &WebviewWindow<R>) {
+    // Minimized windows report placeholder coordinates on some platforms
+    if window.is_minimized().unwrap_or(false) || !window.is_visible().unwrap_or(false) {
+        return;
+    }
+    let (Ok(monitors), Some(frame)) = (window.available_monitors(), current_frame(window)) else {
+        return;
+    };
+    let key = monitor_key(&monitors);
+    let changed = FRAMES.lock().unwrap().insert(key, frame) != Some(frame);
+    if changed {
+        schedule_save();
+    }
+}
+
+/// Saves the float window's frame whenever it is moved or resized
+pub fn track<R: Runtime>(window: &WebviewWindow<R>) {
+    let app = window.app_handle().clone();
+    let label = window.label().to_string();
+    window.on_window_event(move |event| {
+        if matches!(event, WindowEvent::Moved(_) | WindowEvent::Resized(_)) {
+            if let Some(window) = app.get_webview_window(&label) {
+                remember(&window);
+            }
+        }
+    });
+}
+
+/// Moves the float window to where it was last placed with the current
+/// monitors connected, kept within the visible screen area
+pub fn restore<R: Runtime>(window: &WebviewWindow<R>) {
+    let Ok(monitors) = window.available_monitors() else {
+        return;
+    };
+    let primary = window.primary_monitor().ok().flatten();
+    if let Some(frame) = target_frame(&monitors, primary.as_ref()) {
+        apply_frame(window, frame);
+    }
+}
+
+/// Where to build a new float window, in logical pixels, so it opens where
+/// `restore` would move it
+pub fn initial_position<R: Runtime>(app: &AppHandle<R>) -> (f64, f64) {
+    let Ok(monitors) = app.available_monitors() else {
+        return DEFAULT_POSITION;
+    };
+    let primary = app.primary_monitor().ok().flatten();
+    let Some(frame) = target_frame(&monitors, primary.as_ref()) else {
+        return DEFAULT_POSITION;
+    };
+    let scale = monitors
+        .iter()
+        .filter(|monitor| overlap(frame, &monitor.work_area()) > 0)
+        .max_by_key(|monitor| overlap(frame, &monitor.work_area()))
+        .or(primary.as_ref())
+        .map_or(1.0, Monitor::scale_factor);
+    (frame.x as f64 / scale, frame.y as f64 / scale)
+}
+
+/// Forgets the saved frame for the current monitor configuration and moves
+/// the float window, if open, back to its default position
+pub fn reset<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
+    FRAMES.lock().unwrap().remove(&monitor_key(&monitors));
+    save_frames();
+
+    let Some(window) = app.get_webview_window("float") else {
+        return Ok(());
+    };
+    let Some(primary) = app.primary_monitor().map_err(|e| e.to_string())? else {
+        return Ok(());
+    };
+    let frame = default_frame(&primary);
+    window
+        .set_position(PhysicalPosition::new(frame.x, frame.y))
+        .map_err(|e| e.to_string())
+}
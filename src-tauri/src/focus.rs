@@ -0,0 +1,69 @@
+use crate::ReviewItem;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct FocusSettings {
+    pub enabled: bool,
+    pub projects: Vec<String>,
+    /// tmux session names
+    pub sessions: Vec<String>,
+}
+
+impl FocusSettings {
+    /// Focus mode without any project or session picked hides nothing
+    pub fn is_filtering(&self) -> bool {
+        self.enabled && !(self.projects.is_empty() && self.sessions.is_empty())
+    }
+
+    pub fn matches(&self, item: &ReviewItem) -> bool {
+        let listed = |list: &[String], value: &Option<String>| {
+            value.as_ref().is_some_and(|value| list.contains(value))
+        };
+        !self.is_filtering()
+            || listed(&self.projects, &item.project)
+            || listed(&self.sessions, &item.tmux_session)
+    }
+}
+
+/// The pending items focus mode lets through; the rest stay queued
+pub fn visible(queue: &[ReviewItem]) -> Vec<ReviewItem> {
+    let focus = crate::current_settings().focus;
+    queue
+        .iter()
+        .filter(|item| focus.matches(item))
+        .cloned()
+        .collect()
+}
+
+fn toggle_entry(list: &mut Vec<String>, name: &str) {
+    match list.iter().position(|entry| entry == name) {
+        Some(index) => {
+            list.remove(index);
+        }
+        None => list.push(name.to_string()),
+    }
+}
+
+/// Runs an entry of the tray's Focus submenu: `toggle`, `project:<name>` or
+/// `session:<name>`. Picking a project or session turns focus mode on.
+pub fn run_menu_action<R: Runtime>(app: &AppHandle<R>, action: &str) {
+    let mut settings = crate::current_settings();
+    let focus = &mut settings.focus;
+    match action.split_once(':') {
+        None if action == "toggle" => focus.enabled = !focus.enabled,
+        Some(("project", name)) => {
+            toggle_entry(&mut focus.projects, name);
+            focus.enabled = true;
+        }
+        Some(("session", name)) => {
+            toggle_entry(&mut focus.sessions, name);
+            focus.enabled = true;
+        }
+        _ => return,
+    }
+    if let Err(e) = crate::store_settings(app, settings) {
+        println!("[Lovnotifier] Failed to update focus mode: {}", e);
+    }
+}
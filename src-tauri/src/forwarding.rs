@@ -0,0 +1,442 @@
+use crate::{NotifyPayload, Priority, ReviewItem};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Header set on forwarded requests so a receiving Lovnotifier does not
+/// forward them again (two mirrored instances would otherwise ping-pong)
+pub const FORWARDED_HEADER: &str = "x-lovnotifier-forwarded";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const BACKOFF_BASE_SECS: u64 = 5;
+const BACKOFF_MAX_SECS: u64 = 3600;
+const IDLE_POLL_SECS: u64 = 60;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ForwardTarget {
+    pub name: String,
+    pub url: String,
+    pub enabled: bool,
+    /// Only forward items from these projects (empty = all)
+    pub projects: Vec<String>,
+    /// Only forward items at or above this priority
+    pub min_priority: Option<Priority>,
+    /// Only forward items carrying at least one of these tags (empty = all)
+    pub tags: Vec<String>,
+    /// Extra request headers, e.g. an API key for the receiving side
+    pub headers: HashMap<String, String>,
+}
+
+impl Default for ForwardTarget {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            url: String::new(),
+            enabled: true,
+            projects: Vec::new(),
+            min_priority: None,
+            tags: Vec::new(),
+            headers: HashMap::new(),
+        }
+    }
+}
+
+impl ForwardTarget {
+    pub fn matches(&self, item: &ReviewItem) -> bool {
+        if !self.enabled || self.url.is_empty() {
+            return false;
+        }
+        if !self.projects.is_empty()
+            && !item
+                .project
+                .as_ref()
+                .is_some_and(|p| self.projects.contains(p))
+        {
+            return false;
+        }
+        if self.min_priority.is_some_and(|min| item.priority < min) {
+            return false;
+        }
+        if !self.tags.is_empty() && !item.tags.iter().any(|t| self.tags.contains(t)) {
+            return false;
+        }
+        true
+    }
+
+    fn key(&self) -> &str {
+        if self.name.is_empty() {
+            &self.url
+        } else {
+            &self.name
+        }
+    }
+}
+
+enum DeliveryError {
+    /// Network errors, timeouts and 5xx/408/429 responses
+    Retry(String),
+    /// The receiver refused the item; retrying would not help
+    Rejected(String),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct OutboxEntry {
+    /// Target name (or URL when unnamed), resolved against current settings
+    /// at delivery time so edited URLs and headers take effect
+    target: String,
+    item: ReviewItem,
+    attempts: u32,
+    next_attempt: u64,
+}
+
+static OUTBOX: LazyLock<Mutex<Vec<OutboxEntry>>> = LazyLock::new(|| Mutex::new(load_outbox()));
+
+static WAKE: LazyLock<tokio::sync::Notify> = LazyLock::new(tokio::sync::Notify::new);
+
+fn get_outbox_path() -> PathBuf {
+    crate::get_data_dir().join("forward_outbox.json")
+}
+
+fn load_outbox() -> Vec<OutboxEntry> {
+    fs::read_to_string(get_outbox_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_outbox(outbox: &[OutboxEntry]) {
+    let path = get_outbox_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_string_pretty(outbox) {
+        let _ = fs::write(&path, json);
+    }
+}
+
+fn unix_secs() -> u64 {
+    crate::unix_now().as_secs()
+}
+
+fn backoff_secs(attempts: u32) -> u64 {
+    BACKOFF_BASE_SECS
+        .saturating_mul(1u64 << attempts.min(16))
+        .min(BACKOFF_MAX_SECS)
+}
+
+/// Queues `items` for every forwarding target whose filters match
+pub fn enqueue(items: &[ReviewItem]) {
+    let targets = crate::current_settings().forwarding;
+    if targets.is_empty() {
+        return;
+    }
+
+    let now = unix_secs();
+    let mut outbox = OUTBOX.lock().unwrap();
+    let before = outbox.len();
+    for item in items {
+        for target in targets.iter().filter(|t| t.matches(item)) {
+            outbox.push(OutboxEntry {
+                target: target.key().to_string(),
+                item: item.clone(),
+                attempts: 0,
+                next_attempt: now,
+            });
+        }
+    }
+    if outbox.len() != before {
+        save_outbox(&outbox);
+        WAKE.notify_one();
+    }
+}
+
+/// Delivers the outbox in the background, retrying failures with
+/// exponential backoff. Entries survive restarts via `forward_outbox.json`.
+pub fn start_worker() {
+    tauri::async_runtime::spawn(async move {
+        let client = match reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build() {
+            Ok(client) => client,
+            Err(e) => {
+                println!("[Lovnotifier] Forwarding disabled: {}", e);
+                return;
+            }
+        };
+
+        loop {
+            deliver_due(&client).await;
+
+            let next_due = OUTBOX
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|entry| entry.next_attempt)
+                .min();
+            let wait = next_due
+                .map(|due| due.saturating_sub(unix_secs()).clamp(1, IDLE_POLL_SECS))
+                .unwrap_or(IDLE_POLL_SECS);
+
+            tokio::select! {
+                _ = WAKE.notified() => {}
+                _ = tokio::time::sleep(Duration::from_secs(wait)) => {}
+            }
+        }
+    });
+}
+
+async fn deliver_due(client: &reqwest::Client) {
+    let now = unix_secs();
+    let due: Vec<OutboxEntry> = OUTBOX
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|entry| entry.next_attempt <= now)
+        .cloned()
+        .collect();
+    if due.is_empty() {
+        return;
+    }
+
+    let mut by_target: HashMap<String, Vec<OutboxEntry>> = HashMap::new();
+    for entry in due {
+        by_target
+            .entry(entry.target.clone())
+            .or_default()
+            .push(entry);
+    }
+
+    // One task per target, so a slow receiver does not hold up the others;
+    // each target still gets its items in order
+    let targets = crate::current_settings().forwarding;
+    let tasks: Vec<_> = by_target
+        .into_iter()
+        .map(|(key, entries)| {
+            let client = client.clone();
+            let target = targets.iter().find(|t| t.key() == key).cloned();
+            tauri::async_runtime::spawn(async move {
+                for entry in entries {
+                    let result = match &target {
+                        Some(target) => post(&client, target, &entry.item).await,
+                        // The target was removed from settings; drop its backlog
+                        None => Ok(()),
+                    };
+                    record_result(&entry, result);
+                }
+            })
+        })
+        .collect();
+    for task in tasks {
+        let _ = task.await;
+    }
+}
+
+fn record_result(entry: &OutboxEntry, result: Result<(), DeliveryError>) {
+    let mut outbox = OUTBOX.lock().unwrap();
+    let Some(pos) = outbox
+        .iter()
+        .position(|e| e.target == entry.target && e.item.id == entry.item.id)
+    else {
+        return;
+    };
+    match result {
+        Ok(()) => {
+            outbox.remove(pos);
+        }
+        Err(DeliveryError::Rejected(e)) => {
+            let dropped = outbox.remove(pos);
+            println!(
+                "[Lovnotifier] Forward to {} rejected, dropping #{}: {}",
+                dropped.target, dropped.item.seq, e
+            );
+        }
+        Err(DeliveryError::Retry(e)) => {
+            let pending = &mut outbox[pos];
+            pending.attempts += 1;
+            pending.next_attempt = unix_secs() + backoff_secs(pending.attempts);
+            println!(
+                "[Lovnotifier] Forward to {} failed (attempt {}): {}",
+                pending.target, pending.attempts, e
+            );
+        }
+    }
+    save_outbox(&outbox);
+}
+
+/// The body the receiving `/notify` expects for `item`, with its remaining
+/// lifetime as `ttl_secs`. Local items name this machine as their host, so
+/// the receiver reaches their panes over ssh rather than its own.
+fn forward_payload(item: &ReviewItem, now: u64) -> NotifyPayload {
+    NotifyPayload {
+        title: item.title.clone(),
+        project: item.project.clone(),
+        project_path: item.project_path.clone(),
+        session_id: item.session_id.clone(),
+        mux_kind: item.mux_kind,
+        tmux_session: item.tmux_session.clone(),
+        tmux_window: item.tmux_window.clone(),
+        tmux_pane: item.tmux_pane.clone(),
+        tmux_pane_id: item.tmux_pane_id.clone(),
+        tmux_socket: item.tmux_socket.clone(),
+        tmux_hook: None,
+        host: crate::remote_target(item.host.as_deref(), None)
+            .or_else(|| Some(crate::LOCAL_HOSTNAME.clone())),
+        ssh_target: item.ssh_target.clone(),
+        source: item.source.clone(),
+        link: item.link.clone(),
+        priority: item.priority,
+        sound: item.sound.clone(),
+        tags: item.tags.clone(),
+        ttl_secs: item.expires_at.map(|at| at.saturating_sub(now)),
+        queue: item.queue.clone(),
+        snapshot: item.snapshot.clone(),
+    }
+}
+
+async fn post(
+    client: &reqwest::Client,
+    target: &ForwardTarget,
+    item: &ReviewItem,
+) -> Result<(), DeliveryError> {
+    let now = unix_secs();
+    if item.expires_at.is_some_and(|at| at <= now) {
+        // Expired while waiting in the outbox; the receiver would archive it
+        return Ok(());
+    }
+    let mut request = client
+        .post(&target.url)
+        .header(FORWARDED_HEADER, "1")
+        .json(&forward_payload(item, now));
+    for (name, value) in &target.headers {
+        request = request.header(name, value);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| DeliveryError::Retry(e.to_string()))?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else if status.is_client_error()
+        && status != reqwest::StatusCode::REQUEST_TIMEOUT
+        && status != reqwest::StatusCode::TOO_MANY_REQUESTS
+    {
+        Err(DeliveryError::Rejected(format!("HTTP {}", status)))
+    } else {
+        Err(DeliveryError::Retry(format!("HTTP {}", status)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(project: &str, priority: &str, tags: &[&str]) -> ReviewItem {
+        serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "seq": 1,
+            "title": "Build finished",
+            "project": project,
+            "timestamp": 0,
+            "priority": priority,
+            "tags": tags,
+        }))
+        .unwrap()
+    }
+
+    fn target() -> ForwardTarget {
+        ForwardTarget {
+            url: "http://phone.local:23567/notify".to_string(),
+            ..ForwardTarget::default()
+        }
+    }
+
+    #[test]
+    fn unfiltered_target_matches_everything() {
+        assert!(target().matches(&item("api", "low", &[])));
+    }
+
+    #[test]
+    fn disabled_or_urlless_targets_match_nothing() {
+        let disabled = ForwardTarget {
+            enabled: false,
+            ..target()
+        };
+        assert!(!disabled.matches(&item("api", "urgent", &[])));
+        assert!(!ForwardTarget::default().matches(&item("api", "urgent", &[])));
+    }
+
+    #[test]
+    fn filters_by_project_priority_and_tags() {
+        let filtered = ForwardTarget {
+            projects: vec!["api".to_string()],
+            min_priority: Some(Priority::High),
+            tags: vec!["ci".to_string(), "deploy".to_string()],
+            ..target()
+        };
+        assert!(filtered.matches(&item("api", "high", &["deploy"])));
+        assert!(filtered.matches(&item("api", "urgent", &["ci", "other"])));
+        assert!(!filtered.matches(&item("web", "urgent", &["ci"])));
+        assert!(!filtered.matches(&item("api", "normal", &["ci"])));
+        assert!(!filtered.matches(&item("api", "urgent", &["other"])));
+    }
+
+    #[test]
+    fn project_filter_skips_items_without_a_project() {
+        let filtered = ForwardTarget {
+            projects: vec!["api".to_string()],
+            ..target()
+        };
+        let mut no_project = item("api", "normal", &[]);
+        no_project.project = None;
+        assert!(!filtered.matches(&no_project));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        assert_eq!(backoff_secs(0), BACKOFF_BASE_SECS);
+        assert_eq!(backoff_secs(1), BACKOFF_BASE_SECS * 2);
+        assert_eq!(backoff_secs(3), BACKOFF_BASE_SECS * 8);
+        assert_eq!(backoff_secs(10), BACKOFF_MAX_SECS);
+        assert_eq!(backoff_secs(u32::MAX), BACKOFF_MAX_SECS);
+    }
+
+    #[test]
+    fn forwarded_payload_carries_the_remaining_ttl() {
+        let mut expiring = item("api", "high", &["ci"]);
+        expiring.expires_at = Some(1_000);
+        let payload = forward_payload(&expiring, 900);
+        assert_eq!(payload.ttl_secs, Some(100));
+        assert_eq!(payload.priority, Priority::High);
+        assert_eq!(payload.tags, ["ci"]);
+        assert_eq!(
+            forward_payload(&item("api", "low", &[]), 900).ttl_secs,
+            None
+        );
+    }
+
+    #[test]
+    fn forwarded_local_items_name_this_machine_as_host() {
+        let mut local = item("api", "low", &[]);
+        assert_eq!(
+            forward_payload(&local, 900).host.as_deref(),
+            Some(crate::LOCAL_HOSTNAME.as_str())
+        );
+        local.host = Some("localhost".to_string());
+        assert_eq!(
+            forward_payload(&local, 900).host.as_deref(),
+            Some(crate::LOCAL_HOSTNAME.as_str())
+        );
+
+        let mut remote = item("api", "low", &[]);
+        remote.host = Some("build-box".to_string());
+        assert_eq!(
+            forward_payload(&remote, 900).host.as_deref(),
+            Some("build-box")
+        );
+    }
+}
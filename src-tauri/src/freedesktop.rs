@@ -0,0 +1,369 @@
+//! Native notifications over the freedesktop Notifications D-Bus interface.
+//! One thread posts them and one listens for clicks, however many are on
+//! screen.
+
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use zbus::blocking::{Connection, MessageIterator};
+use zbus::zvariant::Value;
+use zbus::MatchRule;
+
+const DESTINATION: &str = "org.freedesktop.Notifications";
+const PATH: &str = "/org/freedesktop/Notifications";
+const INTERFACE: &str = "org.freedesktop.Notifications";
+
+/// The action servers invoke when the notification itself is clicked
+const DEFAULT_ACTION: &str = "default";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Urgency {
+    Low = 0,
+    Normal = 1,
+    Critical = 2,
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub summary: String,
+    pub body: String,
+    pub urgency: Urgency,
+    /// Sound theme name such as `message-new-instant`
+    pub sound: Option<String>,
+    /// Stays on screen until acted on
+    pub persistent: bool,
+    /// Handed to the click handler
+    pub tag: Option<String>,
+}
+
+/// Tags of the notifications on screen, by server-assigned id
+type Shown = Arc<Mutex<HashMap<u32, Option<String>>>>;
+
+pub struct Notifier {
+    requests: mpsc::Sender<Notification>,
+}
+
+impl Notifier {
+    /// Starts the posting and listening threads on `connection`. `on_click`
+    /// runs on the listening thread with the tag of a clicked notification.
+    pub fn start(
+        connection: Connection,
+        on_click: impl Fn(Option<String>) + Send + 'static,
+    ) -> zbus::Result<Self> {
+        let rule = MatchRule::builder()
+            .msg_type(zbus::message::Type::Signal)
+            .interface(INTERFACE)?
+            .path(PATH)?
+            .build();
+        // Subscribed before anything is posted, so no click can be missed
+        let signals = MessageIterator::for_match_rule(rule, &connection, None)?;
+
+        let shown = Shown::default();
+        let (requests, queue) = mpsc::channel();
+        let listener_shown = shown.clone();
+        std::thread::spawn(move || listen(signals, &listener_shown, on_click));
+        std::thread::spawn(move || post(&connection, queue, &shown));
+        Ok(Self { requests })
+    }
+
+    pub fn show(&self, notification: Notification) {
+        let _ = self.requests.send(notification);
+    }
+}
+
+fn post(connection: &Connection, queue: mpsc::Receiver<Notification>, shown: &Shown) {
+    for notification in queue {
+        let mut hints: HashMap<&str, Value> = HashMap::new();
+        hints.insert("urgency", Value::U8(notification.urgency as u8));
+        if let Some(sound) = &notification.sound {
+            hints.insert("sound-name", Value::from(sound.as_str()));
+        }
+        // -1 leaves the timeout to the server, 0 never expires
+        let timeout: i32 = if notification.persistent { 0 } else { -1 };
+        let body = (
+            "Lovnotifier",
+            0u32,
+            "",
+            notification.summary.as_str(),
+            notification.body.as_str(),
+            vec![DEFAULT_ACTION, "Open"],
+            hints,
+            timeout,
+        );
+        let id = connection
+            .call_method(Some(DESTINATION), PATH, Some(INTERFACE), "Notify", &body)
+            .and_then(|reply| reply.body().deserialize::<u32>());
+        match id {
+            Ok(id) => {
+                shown.lock().unwrap().insert(id, notification.tag);
+            }
+            Err(e) => println!("[Lovnotifier] Native notification failed: {}", e),
+        }
+    }
+}
+
+fn listen(signals: MessageIterator, shown: &Shown, on_click: impl Fn(Option<String>)) {
+    for message in signals.flatten() {
+        let header = message.header();
+        match header.member().map(|member| member.as_str()) {
+            Some("ActionInvoked") => {
+                let Ok((id, action)) = message.body().deserialize::<(u32, String)>() else {
+                    continue;
+                };
+                // Other applications' notifications are not in the map
+                let tag = shown.lock().unwrap().remove(&id);
+                if let (Some(tag), DEFAULT_ACTION) = (tag, action.as_str()) {
+                    on_click(tag);
+                }
+            }
+            Some("NotificationClosed") => {
+                if let Ok((id, _reason)) = message.body().deserialize::<(u32, u32)>() {
+                    shown.lock().unwrap().remove(&id);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixStream;
+    use std::time::Duration;
+    use zbus::zvariant::OwnedValue;
+
+    /// What the mock daemon was asked to show
+    #[derive(Debug)]
+    struct Posted {
+        summary: String,
+        actions: Vec<String>,
+        urgency: u8,
+        sound: Option<String>,
+        timeout: i32,
+    }
+
+    struct MockDaemon {
+        next_id: u32,
+        shown: mpsc::Sender<Posted>,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.Notifications")]
+    impl MockDaemon {
+        #[allow(clippy::too_many_arguments)]
+        fn notify(
+            &mut self,
+            _app_name: String,
+            _replaces_id: u32,
+            _app_icon: String,
+            summary: String,
+            _body: String,
+            actions: Vec<String>,
+            hints: HashMap<String, OwnedValue>,
+            expire_timeout: i32,
+        ) -> u32 {
+            self.next_id += 1;
+            let _ = self.shown.send(Posted {
+                summary,
+                actions,
+                urgency: hints
+                    .get("urgency")
+                    .and_then(|value| u8::try_from(value).ok())
+                    .unwrap_or(1),
+                sound: hints
+                    .get("sound-name")
+                    .and_then(|value| String::try_from(value.try_clone().ok()?).ok()),
+                timeout: expire_timeout,
+            });
+            self.next_id
+        }
+    }
+
+    /// A notifier talking to a mock daemon over a private connection, the
+    /// daemon's end of it, and the tags of clicked notifications
+    fn connect() -> (
+        Notifier,
+        Connection,
+        mpsc::Receiver<Posted>,
+        mpsc::Receiver<Option<String>>,
+    ) {
+        let (daemon_stream, client_stream) = UnixStream::pair().unwrap();
+        let (shown, shown_rx) = mpsc::channel();
+        let daemon = std::thread::spawn(move || {
+            zbus::blocking::connection::Builder::unix_stream(daemon_stream)
+                .server(zbus::Guid::generate())
+                .unwrap()
+                .p2p()
+                .serve_at(PATH, MockDaemon { next_id: 0, shown })
+                .unwrap()
+                .build()
+                .unwrap()
+        });
+        let client = zbus::blocking::connection::Builder::unix_stream(client_stream)
+            .p2p()
+            .build()
+            .unwrap();
+        let daemon = daemon.join().unwrap();
+
+        let (clicks, clicks_rx) = mpsc::channel();
+        let notifier = Notifier::start(client, move |tag| {
+            let _ = clicks.send(tag);
+        })
+        .unwrap();
+        (notifier, daemon, shown_rx, clicks_rx)
+    }
+
+    fn notification(summary: &str, tag: Option<&str>) -> Notification {
+        Notification {
+            summary: summary.to_string(),
+            body: String::new(),
+            urgency: Urgency::Normal,
+            sound: None,
+            persistent: false,
+            tag: tag.map(str::to_string),
+        }
+    }
+
+    fn emit(
+        daemon: &Connection,
+        member: &str,
+        body: &(impl serde::Serialize + zbus::zvariant::DynamicType),
+    ) {
+        daemon
+            .emit_signal(None::<()>, PATH, INTERFACE, member, body)
+            .unwrap();
+    }
+
+    const WAIT: Duration = Duration::from_secs(5);
+
+    /// A private `dbus-daemon --session`, stopped when dropped
+    struct SessionBus {
+        daemon: std::process::Child,
+        address: String,
+    }
+
+    impl SessionBus {
+        /// `None` when `dbus-daemon` is not installed
+        fn start() -> Option<Self> {
+            use std::io::BufRead;
+
+            let mut daemon = std::process::Command::new("dbus-daemon")
+                .args(["--session", "--nofork", "--print-address"])
+                .stdout(std::process::Stdio::piped())
+                .stderr(std::process::Stdio::null())
+                .spawn()
+                .ok()?;
+            let mut address = String::new();
+            std::io::BufReader::new(daemon.stdout.take()?)
+                .read_line(&mut address)
+                .ok()?;
+            Some(Self {
+                daemon,
+                address: address.trim().to_string(),
+            })
+        }
+
+        fn builder(&self) -> zbus::blocking::connection::Builder<'static> {
+            zbus::blocking::connection::Builder::address(self.address.as_str()).unwrap()
+        }
+    }
+
+    impl Drop for SessionBus {
+        fn drop(&mut self) {
+            let _ = self.daemon.kill();
+            let _ = self.daemon.wait();
+        }
+    }
+
+    #[test]
+    fn posts_with_a_default_action_and_hints() {
+        let (notifier, _daemon, shown, _clicks) = connect();
+        notifier.show(Notification {
+            urgency: Urgency::Critical,
+            sound: Some("message-new-instant".to_string()),
+            persistent: true,
+            ..notification("Deploy failed", Some("1"))
+        });
+        notifier.show(notification("Build passed", None));
+
+        let first = shown.recv_timeout(WAIT).unwrap();
+        assert_eq!(first.summary, "Deploy failed");
+        assert_eq!(first.actions, ["default", "Open"]);
+        assert_eq!(first.urgency, 2);
+        assert_eq!(first.sound.as_deref(), Some("message-new-instant"));
+        assert_eq!(first.timeout, 0);
+
+        let second = shown.recv_timeout(WAIT).unwrap();
+        assert_eq!(second.urgency, 1);
+        assert_eq!(second.sound, None);
+        assert_eq!(second.timeout, -1);
+    }
+
+    #[test]
+    fn clicks_report_the_tag_once() {
+        let (notifier, daemon, shown, clicks) = connect();
+        notifier.show(notification("Review ready", Some("item-1")));
+        notifier.show(notification("2 new notifications", None));
+        shown.recv_timeout(WAIT).unwrap();
+        shown.recv_timeout(WAIT).unwrap();
+        // Let the poster record the ids the daemon returned
+        std::thread::sleep(Duration::from_millis(100));
+
+        emit(&daemon, "ActionInvoked", &(1u32, "default"));
+        assert_eq!(
+            clicks.recv_timeout(WAIT).unwrap().as_deref(),
+            Some("item-1")
+        );
+        emit(&daemon, "ActionInvoked", &(1u32, "default"));
+        emit(&daemon, "ActionInvoked", &(2u32, "default"));
+        assert_eq!(clicks.recv_timeout(WAIT).unwrap(), None);
+        assert!(clicks.recv_timeout(Duration::from_millis(200)).is_err());
+    }
+
+    #[test]
+    fn closed_or_foreign_notifications_are_not_clicks() {
+        let (notifier, daemon, shown, clicks) = connect();
+        notifier.show(notification("Review ready", Some("item-1")));
+        shown.recv_timeout(WAIT).unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+
+        emit(&daemon, "ActionInvoked", &(7u32, "default"));
+        emit(&daemon, "ActionInvoked", &(1u32, "snooze"));
+        emit(&daemon, "NotificationClosed", &(1u32, 2u32));
+        emit(&daemon, "ActionInvoked", &(1u32, "default"));
+        assert!(clicks.recv_timeout(Duration::from_millis(300)).is_err());
+    }
+
+    #[test]
+    fn posts_and_reports_clicks_through_a_session_bus() {
+        let Some(bus) = SessionBus::start() else {
+            println!("dbus-daemon is not installed; skipping");
+            return;
+        };
+        let (shown, shown_rx) = mpsc::channel();
+        let daemon = bus
+            .builder()
+            .name(DESTINATION)
+            .unwrap()
+            .serve_at(PATH, MockDaemon { next_id: 0, shown })
+            .unwrap()
+            .build()
+            .unwrap();
+        let (clicks, clicks_rx) = mpsc::channel();
+        let notifier = Notifier::start(bus.builder().build().unwrap(), move |tag| {
+            let _ = clicks.send(tag);
+        })
+        .unwrap();
+
+        notifier.show(notification("Review ready", Some("item-1")));
+        assert_eq!(shown_rx.recv_timeout(WAIT).unwrap().summary, "Review ready");
+        std::thread::sleep(Duration::from_millis(100));
+
+        // Broadcast by the bus, as a real notification server's would be
+        emit(&daemon, "ActionInvoked", &(1u32, "default"));
+        assert_eq!(
+            clicks_rx.recv_timeout(WAIT).unwrap().as_deref(),
+            Some("item-1")
+        );
+    }
+}
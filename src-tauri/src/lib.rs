@@ -1,10 +1,36 @@
+mod dnd;
+mod float_position;
+mod focus;
+mod forwarding;
+#[cfg(target_os = "linux")]
+mod freedesktop;
+mod notifications;
+mod rate_limit;
+mod rules;
+#[cfg(desktop)]
+mod shortcuts;
+mod terminal;
+mod tmux_hooks;
+mod tmux_watch;
+mod tray;
+mod tray_icon;
+mod webhooks;
+
+use dnd::DndSettings;
+use focus::FocusSettings;
+use forwarding::ForwardTarget;
+use notifications::NotificationSettings;
+use rate_limit::{RateLimitSettings, RateLimiter};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::LazyLock;
 use std::sync::Mutex;
-use tauri::{menu::Menu, tray::TrayIconBuilder, Emitter, Manager};
+use tauri::{tray::TrayIconBuilder, Emitter, Manager};
+use terminal::{MuxKind, MuxTarget, NavigationResult, SnapshotSettings, TerminalSettings};
+use tmux_watch::TmuxWatchSettings;
+use tray::TraySettings;
 use warp::Filter;
 
 #[cfg(target_os = "macos")]
@@ -27,19 +53,178 @@ pub struct ReviewItem {
     pub title: String,
     pub project: Option<String>,
     pub timestamp: u64,
+    /// Multiplexer the `tmux_*` coordinates refer to; for Zellij the window
+    /// is the tab
+    #[serde(default)]
+    pub mux_kind: MuxKind,
     pub tmux_session: Option<String>,
     pub tmux_window: Option<String>,
     pub tmux_pane: Option<String>,
+    /// Stable tmux pane id such as `%42`
+    pub tmux_pane_id: Option<String>,
+    /// tmux server socket name (`-L`) or path (`-S`); unset for the default server
+    pub tmux_socket: Option<String>,
+    /// Machine the notification was sent from
+    pub host: Option<String>,
+    /// ssh destination for reaching `host`, if it differs from the host name
+    pub ssh_target: Option<String>,
     pub session_id: Option<String>,
     pub project_path: Option<String>,
+    pub source: Option<String>,
+    pub link: Option<String>,
+    #[serde(default)]
+    pub priority: Priority,
+    /// Sound the native notification plays, e.g. `Glass` on macOS or a
+    /// sound theme name such as `message-new-instant` on Linux
+    pub sound: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Unix time after which the item is archived automatically
+    pub expires_at: Option<u64>,
+    /// Unix time a snoozed item returns to the pending queue
+    pub snoozed_until: Option<u64>,
+    /// Named queue a rule or sender routed the item to
+    pub queue: Option<String>,
+    /// Why the last attempt to navigate to this item failed
+    pub nav_error: Option<String>,
+    /// Unix time since which the item's tmux pane has been missing
+    pub stale_since: Option<u64>,
+    /// Last lines of the sender's tmux pane when the notification arrived
+    pub snapshot: Option<String>,
+    /// How the item left the pending queue (completed items only)
+    pub outcome: Option<Outcome>,
+}
+
+/// ssh destination for an item from `host`, or `None` when it was sent from
+/// this machine. An explicit `ssh_target` always counts as remote.
+fn remote_target(host: Option<&str>, ssh_target: Option<&str>) -> Option<String> {
+    if let Some(target) = ssh_target.filter(|t| !t.is_empty()) {
+        return Some(target.to_string());
+    }
+    let host = host.filter(|h| !h.is_empty())?;
+    let short = |name: &str| name.split('.').next().unwrap_or(name).to_ascii_lowercase();
+    if host == "localhost" || short(host) == short(&LOCAL_HOSTNAME) {
+        None
+    } else {
+        Some(host.to_string())
+    }
+}
+
+impl ReviewItem {
+    fn remote_target(&self) -> Option<String> {
+        remote_target(self.host.as_deref(), self.ssh_target.as_deref())
+    }
+
+    /// Where navigation should go, if the item came from a multiplexer at all
+    fn mux_target(&self) -> Option<MuxTarget> {
+        if self.tmux_pane_id.is_none() && self.tmux_session.is_none() {
+            return None;
+        }
+        Some(MuxTarget {
+            kind: self.mux_kind,
+            socket: self.tmux_socket.clone(),
+            pane_id: self.tmux_pane_id.clone(),
+            session: self.tmux_session.clone().unwrap_or_default(),
+            window: self.tmux_window.clone().unwrap_or_default(),
+            pane: self.tmux_pane.clone().unwrap_or_default(),
+            ssh_target: self.remote_target(),
+            project_path: self.project_path.clone(),
+            session_id: self.session_id.clone(),
+        })
+    }
+
+    /// Whether both items point at the same multiplexer pane
+    fn same_mux_target(&self, other: &ReviewItem) -> bool {
+        if self.mux_kind != other.mux_kind
+            || self.tmux_socket != other.tmux_socket
+            || self.remote_target() != other.remote_target()
+        {
+            return false;
+        }
+        match (&self.tmux_pane_id, &other.tmux_pane_id) {
+            (Some(a), Some(b)) => a == b,
+            _ => {
+                self.tmux_session == other.tmux_session
+                    && self.tmux_window == other.tmux_window
+                    && self.tmux_pane == other.tmux_pane
+            }
+        }
+    }
+}
+
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Urgent,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Navigated,
+    Dismissed,
+    Expired,
+    /// A rule archived the item on arrival
+    AutoDismissed,
+    /// The user switched to the item's pane without going through Lovnotifier
+    SeenInTerminal,
+    /// The item's tmux pane stayed gone for the configured grace period
+    Stale,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutAction {
+    ConsumeOldest,
+    ConsumeNewest,
+    /// Highest priority first, oldest among equals
+    ConsumeHighestPriority,
+    /// Completes the oldest item without navigating to it
+    DismissOldest,
+    ToggleFloatWindow,
+    /// Focuses the float window with the pending list open for the keyboard
+    QuickPicker,
+    /// Navigates to the next pending item without completing it
+    CyclePending,
+    /// Reaches settings while the menu bar icon is turned off
+    OpenSettings,
+    /// Turns do-not-disturb on, or off including the current quiet hours
+    ToggleDnd,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KeyBinding {
+    pub accelerator: String,
+    pub action: ShortcutAction,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct NotifierSettings {
+    /// Native desktop notifications for new items
     pub notify: bool,
     pub float_window: bool,
     pub menu_bar: bool,
+    /// Consumes the oldest item
     pub shortcut: String,
+    /// Further global shortcuts
+    pub keymap: Vec<KeyBinding>,
+    pub rate_limit: RateLimitSettings,
+    pub forwarding: Vec<ForwardTarget>,
+    pub terminal: TerminalSettings,
+    pub tmux_watch: TmuxWatchSettings,
+    pub snapshot: SnapshotSettings,
+    pub notifications: NotificationSettings,
+    pub dnd: DndSettings,
+    /// Limits what the float window, tray and shortcuts surface
+    pub focus: FocusSettings,
+    pub tray: TraySettings,
 }
 
 impl Default for NotifierSettings {
@@ -49,6 +234,16 @@ impl Default for NotifierSettings {
             float_window: true,
             menu_bar: true,
             shortcut: "F4".to_string(),
+            keymap: Vec::new(),
+            rate_limit: RateLimitSettings::default(),
+            forwarding: Vec::new(),
+            terminal: TerminalSettings::default(),
+            tmux_watch: TmuxWatchSettings::default(),
+            snapshot: SnapshotSettings::default(),
+            notifications: NotificationSettings::default(),
+            dnd: DndSettings::default(),
+            focus: FocusSettings::default(),
+            tray: TraySettings::default(),
         }
     }
 }
@@ -59,12 +254,30 @@ static REVIEW_QUEUE: LazyLock<Mutex<Vec<ReviewItem>>> = LazyLock::new(|| Mutex::
 // Global completed queue
 static COMPLETED_QUEUE: LazyLock<Mutex<Vec<ReviewItem>>> = LazyLock::new(|| Mutex::new(Vec::new()));
 
+// Items snoozed from the tray until they return to the review queue
+static SNOOZED_QUEUE: LazyLock<Mutex<Vec<ReviewItem>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
 // Global auto-increment sequence number
 static REVIEW_SEQ: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
 
+// Current settings, loaded once and replaced by save_settings
+static SETTINGS: LazyLock<Mutex<NotifierSettings>> = LazyLock::new(|| Mutex::new(load_settings()));
+
+// Per-source rate limiter for the notification server
+static RATE_LIMITER: LazyLock<Mutex<RateLimiter>> =
+    LazyLock::new(|| Mutex::new(RateLimiter::default()));
+
+// Name of this machine, to tell local notifications from remote ones
+static LOCAL_HOSTNAME: LazyLock<String> =
+    LazyLock::new(|| gethostname::gethostname().to_string_lossy().into_owned());
+
 // Notification server port
 const NOTIFY_SERVER_PORT: u16 = 23567;
 
+// How often suppressed notifications are folded into summary items, and
+// expired, snoozed and quiet-hour state is refreshed
+const HOUSEKEEPING_INTERVAL_SECS: u64 = 5;
+
 // ============================================================================
 // Path Helpers
 // ============================================================================
@@ -89,6 +302,10 @@ fn get_completed_queue_path() -> PathBuf {
     get_data_dir().join("completed_queue.jsonl")
 }
 
+fn get_snoozed_queue_path() -> PathBuf {
+    get_data_dir().join("snoozed_queue.json")
+}
+
 fn get_review_seq_path() -> PathBuf {
     get_data_dir().join("review_seq")
 }
@@ -131,6 +348,27 @@ fn save_review_queue() {
     }
 }
 
+fn load_snoozed_queue() {
+    let path = get_snoozed_queue_path();
+    if let Ok(content) = fs::read_to_string(&path) {
+        match serde_json::from_str::<Vec<ReviewItem>>(&content) {
+            Ok(items) => *SNOOZED_QUEUE.lock().unwrap() = items,
+            Err(e) => println!("[Lovnotifier] Failed to parse snoozed queue: {}", e),
+        }
+    }
+}
+
+fn save_snoozed_queue() {
+    let path = get_snoozed_queue_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let snoozed = SNOOZED_QUEUE.lock().unwrap();
+    if let Ok(json) = serde_json::to_string_pretty(&*snoozed) {
+        let _ = fs::write(&path, json);
+    }
+}
+
 fn load_completed_queue() {
     let path = get_completed_queue_path();
     if path.exists() {
@@ -171,13 +409,18 @@ fn load_review_seq() {
 }
 
 fn next_review_seq() -> u64 {
-    let seq = REVIEW_SEQ.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    reserve_review_seqs(1).start
+}
+
+/// Reserves `count` consecutive seq values with a single persistence write
+fn reserve_review_seqs(count: u64) -> std::ops::Range<u64> {
+    let start = REVIEW_SEQ.fetch_add(count, std::sync::atomic::Ordering::SeqCst);
     let path = get_review_seq_path();
     if let Some(parent) = path.parent() {
         let _ = fs::create_dir_all(parent);
     }
-    let _ = fs::write(&path, (seq + 1).to_string());
-    seq
+    let _ = fs::write(&path, (start + count).to_string());
+    start..start + count
 }
 
 fn migrate_from_lovcode() {
@@ -207,8 +450,7 @@ fn migrate_from_lovcode() {
 // Settings Commands
 // ============================================================================
 
-#[tauri::command]
-fn get_settings() -> NotifierSettings {
+fn load_settings() -> NotifierSettings {
     let path = get_settings_path();
     if path.exists() {
         if let Ok(content) = fs::read_to_string(&path) {
@@ -220,15 +462,79 @@ fn get_settings() -> NotifierSettings {
     NotifierSettings::default()
 }
 
+fn current_settings() -> NotifierSettings {
+    SETTINGS.lock().unwrap().clone()
+}
+
 #[tauri::command]
-fn save_settings(settings: NotifierSettings) -> Result<(), String> {
+fn get_settings() -> NotifierSettings {
+    current_settings()
+}
+
+#[tauri::command]
+fn save_settings(app: tauri::AppHandle, settings: NotifierSettings) -> Result<(), String> {
+    store_settings(&app, settings)
+}
+
+fn write_settings(json: &str) -> Result<(), String> {
     let path = get_settings_path();
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
+    fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+/// Validates, writes and applies new settings
+fn store_settings<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    settings: NotifierSettings,
+) -> Result<(), String> {
+    dnd::validate(&settings.dnd)?;
+    #[cfg(desktop)]
+    shortcuts::validate(&settings)?;
     let json = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
-    fs::write(&path, json).map_err(|e| e.to_string())?;
-    Ok(())
+
+    // Rejects shortcuts another app holds before anything is written, and
+    // puts the previous ones back if the file cannot be
+    #[cfg(desktop)]
+    shortcuts::apply(app, &settings)?;
+    if let Err(e) = write_settings(&json) {
+        #[cfg(desktop)]
+        if let Err(restore_error) = shortcuts::apply(app, &current_settings()) {
+            println!("[Lovnotifier] {}", restore_error);
+        }
+        return Err(e);
+    }
+    let previous = std::mem::replace(&mut *SETTINGS.lock().unwrap(), settings.clone());
+    apply_settings(app, Some(&previous), &settings).map_err(|e| e.to_string())
+}
+
+/// Whether do-not-disturb is on, manually or by schedule
+#[tauri::command]
+fn get_dnd_active() -> bool {
+    dnd::is_active(&current_settings().dnd)
+}
+
+/// Turns focus mode on or off; `projects` and `sessions`, when given,
+/// replace the focused sets
+#[tauri::command]
+fn set_focus_mode(
+    app: tauri::AppHandle,
+    enabled: bool,
+    projects: Option<Vec<String>>,
+    sessions: Option<Vec<String>>,
+) -> Result<FocusSettings, String> {
+    let mut settings = current_settings();
+    settings.focus.enabled = enabled;
+    if let Some(projects) = projects {
+        settings.focus.projects = projects;
+    }
+    if let Some(sessions) = sessions {
+        settings.focus.sessions = sessions;
+    }
+    let focus = settings.focus.clone();
+    store_settings(&app, settings)?;
+    Ok(focus)
 }
 
 // ============================================================================
@@ -242,9 +548,10 @@ fn emit_review_queue(window: tauri::Window, items: Vec<ReviewItem>) -> Result<()
         .map_err(|e| e.to_string())
 }
 
+/// Pending items, limited to the focused projects while focus mode is on
 #[tauri::command]
 fn get_review_queue() -> Vec<ReviewItem> {
-    REVIEW_QUEUE.lock().unwrap().clone()
+    focus::visible(&REVIEW_QUEUE.lock().unwrap())
 }
 
 #[tauri::command]
@@ -257,35 +564,18 @@ fn get_completed_queue(limit: Option<usize>, offset: Option<usize>) -> Vec<Revie
 
 #[tauri::command]
 fn dismiss_review_item(app_handle: tauri::AppHandle, id: String) -> Result<(), String> {
-    let dismissed_item = {
-        let mut queue = REVIEW_QUEUE.lock().unwrap();
-        let pos = queue.iter().position(|item| item.id == id);
-        pos.map(|i| queue.remove(i))
-    };
-
-    if let Some(mut item) = dismissed_item {
-        item.timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-
-        {
-            let mut completed = COMPLETED_QUEUE.lock().unwrap();
-            completed.push(item.clone());
-        }
-
-        persist_completed_item(&item);
-
-        let pending = REVIEW_QUEUE.lock().unwrap().clone();
-        let _ = app_handle.emit("review-queue-update", pending);
-
-        update_tray_menu(&app_handle);
-        save_review_queue();
-    }
-
+    complete_review_items(&app_handle, &[id], Outcome::Dismissed);
     Ok(())
 }
 
+#[tauri::command]
+fn test_notification_rules(
+    payload: NotifyPayload,
+    rules: Option<String>,
+) -> Result<rules::RuleTestResult, String> {
+    rules::test(payload, rules)
+}
+
 #[tauri::command]
 fn clear_completed_queue() -> Result<(), String> {
     {
@@ -304,133 +594,809 @@ fn clear_completed_queue() -> Result<(), String> {
 // ============================================================================
 
 #[tauri::command]
-fn navigate_to_tmux_pane(session: String, window: String, pane: String) -> Result<(), String> {
+async fn navigate_to_tmux_pane(
+    session: Option<String>,
+    window: Option<String>,
+    pane: Option<String>,
+    pane_id: Option<String>,
+    socket: Option<String>,
+    item_id: Option<String>,
+) -> NavigationResult {
+    // A pending item carries the full target, including its host and the
+    // project details navigation profiles may use
+    let item_target = item_id.and_then(|id| {
+        let queue = REVIEW_QUEUE.lock().unwrap();
+        queue.iter().find(|item| item.id == id)?.mux_target()
+    });
+    let target = item_target.unwrap_or(MuxTarget {
+        socket,
+        pane_id,
+        session: session.unwrap_or_default(),
+        window: window.unwrap_or_default(),
+        pane: pane.unwrap_or_default(),
+        ..MuxTarget::default()
+    });
+    tauri::async_runtime::spawn_blocking(move || navigate_to_target(&target))
+        .await
+        .unwrap_or_default()
+}
+
+/// Navigates to a pending item and completes it, like clicking it in the
+/// tray. A failed navigation leaves the item pending with its `nav_error`
+/// set and returns that reason.
+#[tauri::command]
+async fn open_review_item(app_handle: tauri::AppHandle, id: String) -> Result<(), String> {
+    let item = {
+        let queue = REVIEW_QUEUE.lock().unwrap();
+        queue.iter().find(|item| item.id == id).cloned()
+    };
+    let item = item.ok_or_else(|| format!("no pending item {}", id))?;
+    tauri::async_runtime::spawn_blocking(move || {
+        navigate_review_item(&app_handle, &item)?;
+        complete_review_items(&app_handle, &[item.id], Outcome::Navigated);
+        Ok(())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Blocks until the terminal and multiplexer answered, which for a remote
+/// target means several ssh round trips. Never call it on the main thread.
+fn navigate_to_target(target: &MuxTarget) -> NavigationResult {
     println!(
-        "[Lovnotifier] Navigate to tmux: session={}, window={}, pane={}",
-        session, window, pane
+        "[Lovnotifier] Navigate to tmux: session={}, window={}, pane={}, pane_id={:?}, socket={:?}, ssh={:?}",
+        target.session, target.window, target.pane, target.pane_id, target.socket, target.ssh_target
     );
 
-    #[cfg(target_os = "macos")]
-    {
-        let script = format!(
-            r#"
-            tell application "iTerm2"
-                activate
-                repeat with w in windows
-                    repeat with t in tabs of w
-                        repeat with s in sessions of t
-                            if name of s contains "{}" then
-                                select w
-                                select t
-                                select s
-                                return "FOUND"
-                            end if
-                        end repeat
-                    end repeat
-                end repeat
-                return "NOT_FOUND"
-            end tell
-        "#,
-            session
-        );
+    let settings = current_settings().terminal;
+    let backend = terminal::backend_for(&settings);
+    let ssh_runner;
+    let runner: &dyn terminal::CommandRunner = match &target.ssh_target {
+        Some(ssh_target) => {
+            ssh_runner = terminal::SshRunner {
+                inner: &terminal::SystemRunner,
+                command: &settings.ssh_command,
+                target: ssh_target,
+            };
+            &ssh_runner
+        }
+        None => &terminal::SystemRunner,
+    };
+    tmux_watch::note_navigation(target);
+    let result = terminal::navigate(backend.as_ref(), runner, target);
+    if let Some(error) = result.error_message() {
+        println!("[Lovnotifier] Navigation failed: {}", error);
+    }
+    result
+}
+
+// ============================================================================
+// tmux Hooks
+// ============================================================================
 
-        let _ = std::process::Command::new("osascript")
-            .args(["-e", &script])
-            .output();
+#[tauri::command]
+fn install_tmux_hooks(persist: Option<bool>, socket: Option<String>) -> tmux_hooks::HooksReport {
+    let report = tmux_hooks::install(
+        &terminal::SystemRunner,
+        socket.as_deref(),
+        persist.unwrap_or(false),
+    );
+    log_hooks_report("install", &report);
+    report
+}
 
-        if !window.is_empty() {
-            let _ = std::process::Command::new("tmux")
-                .args(["select-window", "-t", &format!("{}:{}", session, window)])
-                .output();
-        }
-        if !pane.is_empty() {
-            let _ = std::process::Command::new("tmux")
-                .args([
-                    "select-pane",
-                    "-t",
-                    &format!("{}:{}.{}", session, window, pane),
-                ])
-                .output();
-        }
+#[tauri::command]
+fn uninstall_tmux_hooks(socket: Option<String>) -> tmux_hooks::HooksReport {
+    let report = tmux_hooks::uninstall(&terminal::SystemRunner, socket.as_deref());
+    log_hooks_report("uninstall", &report);
+    report
+}
+
+fn log_hooks_report(action: &str, report: &tmux_hooks::HooksReport) {
+    println!(
+        "[Lovnotifier] tmux hooks {}: server={}, config={:?}",
+        action, report.server, report.config
+    );
+    for error in &report.errors {
+        println!("[Lovnotifier] tmux hooks {} error: {}", action, error);
     }
+}
 
-    Ok(())
+/// Handles command-line flags that do not need the app window, returning
+/// the exit code when one was handled:
+///
+/// - `--install-tmux-hooks [--persist] [--socket NAME]`
+/// - `--uninstall-tmux-hooks [--socket NAME]`
+pub fn run_cli(args: &[String]) -> Option<i32> {
+    let socket = args
+        .iter()
+        .position(|arg| arg == "--socket")
+        .and_then(|index| args.get(index + 1))
+        .cloned();
+    let report = if args.iter().any(|arg| arg == "--install-tmux-hooks") {
+        install_tmux_hooks(Some(args.iter().any(|arg| arg == "--persist")), socket)
+    } else if args.iter().any(|arg| arg == "--uninstall-tmux-hooks") {
+        uninstall_tmux_hooks(socket)
+    } else {
+        return None;
+    };
+    Some(if report.errors.is_empty() { 0 } else { 1 })
 }
 
 // ============================================================================
 // Notification HTTP Server
 // ============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct NotifyPayload {
     title: String,
     project: Option<String>,
     project_path: Option<String>,
     session_id: Option<String>,
+    #[serde(default)]
+    mux_kind: MuxKind,
+    #[serde(alias = "mux_session")]
     tmux_session: Option<String>,
+    #[serde(alias = "mux_tab", alias = "mux_window")]
     tmux_window: Option<String>,
+    #[serde(alias = "mux_pane")]
     tmux_pane: Option<String>,
+    tmux_pane_id: Option<String>,
+    tmux_socket: Option<String>,
+    /// Event a tmux hook from `--install-tmux-hooks` reports
+    tmux_hook: Option<String>,
+    host: Option<String>,
+    ssh_target: Option<String>,
+    source: Option<String>,
+    link: Option<String>,
+    #[serde(default)]
+    priority: Priority,
+    sound: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    ttl_secs: Option<u64>,
+    queue: Option<String>,
+    /// Pane output captured by the sender, e.g. a forwarding Lovnotifier
+    snapshot: Option<String>,
+}
+
+fn unix_now() -> std::time::Duration {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+fn build_review_item(payload: NotifyPayload, seq: u64) -> ReviewItem {
+    let now = unix_now();
+    ReviewItem {
+        // The seq suffix keeps ids unique when a batch lands within one millisecond
+        id: format!("{}-{}", now.as_millis(), seq),
+        seq,
+        title: payload.title,
+        project: payload.project,
+        timestamp: now.as_secs(),
+        mux_kind: payload.mux_kind,
+        tmux_session: payload.tmux_session,
+        tmux_window: payload.tmux_window,
+        tmux_pane: payload.tmux_pane,
+        tmux_pane_id: payload.tmux_pane_id,
+        tmux_socket: payload.tmux_socket,
+        host: payload.host,
+        ssh_target: payload.ssh_target,
+        session_id: payload.session_id,
+        project_path: payload.project_path,
+        source: payload.source,
+        link: payload.link,
+        priority: payload.priority,
+        sound: payload.sound,
+        tags: payload.tags,
+        expires_at: payload.ttl_secs.map(|ttl| now.as_secs() + ttl),
+        snoozed_until: None,
+        queue: payload.queue,
+        nav_error: None,
+        stale_since: None,
+        snapshot: payload.snapshot,
+        outcome: None,
+    }
+}
+
+/// What the rules decided to do with an incoming notification
+enum Admission {
+    Dropped,
+    Queued(ReviewItem),
+    Dismissed(ReviewItem),
+}
+
+/// A notification the rules let through, waiting for its seq
+struct Admitted {
+    payload: NotifyPayload,
+    auto_dismiss: bool,
+}
+
+/// Completes the payload and applies the rules; `None` if a rule dropped it
+fn apply_rules(sender: &Sender, mut payload: NotifyPayload) -> Option<Admitted> {
+    if !sender.forwarded {
+        tmux_hooks::complete_payload(&terminal::SystemRunner, &mut payload);
+    }
+    let outcome = rules::evaluate(&mut payload);
+    if !outcome.matched.is_empty() {
+        println!(
+            "[Lovnotifier] Rules {:?} applied to {:?}",
+            outcome.matched, payload.title
+        );
+    }
+    if outcome.dropped {
+        return None;
+    }
+    Some(Admitted {
+        payload,
+        auto_dismiss: outcome.auto_dismiss,
+    })
+}
+
+fn build_admitted(sender: &Sender, admitted: Admitted, seq: u64) -> Admission {
+    let mut item = build_review_item(admitted.payload, seq);
+    // Forwarded items point at tmux panes on the sending machine
+    if !sender.forwarded {
+        capture_snapshot(&mut item);
+    }
+    if admitted.auto_dismiss {
+        Admission::Dismissed(item)
+    } else {
+        Admission::Queued(item)
+    }
+}
+
+fn admit_notification(sender: &Sender, payload: NotifyPayload) -> Admission {
+    match apply_rules(sender, payload) {
+        Some(admitted) => build_admitted(sender, admitted, next_review_seq()),
+        None => Admission::Dropped,
+    }
+}
+
+/// Stores the tail of the item's tmux pane on it, if enabled in settings
+fn capture_snapshot(item: &mut ReviewItem) {
+    let settings = current_settings().snapshot;
+    // Capturing over ssh could stall the request; remote senders can attach
+    // their own snapshot instead
+    if !settings.enabled || item.snapshot.is_some() || item.remote_target().is_some() {
+        return;
+    }
+    if let Some(target) = item.mux_target() {
+        item.snapshot = terminal::capture_pane(&terminal::SystemRunner, &target, settings.lines);
+    }
+}
+
+/// Moves items into the completed history, stamped with the completion time
+fn archive_review_items(items: Vec<ReviewItem>, outcome: Outcome) {
+    let now = unix_now().as_secs();
+    let mut completed = COMPLETED_QUEUE.lock().unwrap();
+    for mut item in items {
+        item.timestamp = now;
+        item.outcome = Some(outcome);
+        persist_completed_item(&item);
+        completed.push(item);
+    }
+}
+
+/// Removes the pending items with the given ids and archives them
+fn complete_review_items<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    ids: &[String],
+    outcome: Outcome,
+) {
+    let completed: Vec<ReviewItem> = {
+        let mut queue = REVIEW_QUEUE.lock().unwrap();
+        let (completed, pending) = queue.drain(..).partition(|item| ids.contains(&item.id));
+        *queue = pending;
+        completed
+    };
+    if completed.is_empty() {
+        return;
+    }
+
+    archive_review_items(completed, outcome);
+    publish_review_queue(app);
+}
+
+/// Archives pending items whose TTL ran out
+fn expire_review_items<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
+    let now = unix_now().as_secs();
+    let expired: Vec<ReviewItem> = {
+        let mut queue = REVIEW_QUEUE.lock().unwrap();
+        let (expired, pending) = queue
+            .drain(..)
+            .partition(|item| item.expires_at.is_some_and(|at| at <= now));
+        *queue = pending;
+        expired
+    };
+    if expired.is_empty() {
+        return;
+    }
+
+    println!("[Lovnotifier] Expired {} review items", expired.len());
+    archive_review_items(expired, Outcome::Expired);
+    publish_review_queue(app);
+}
+
+/// Moves a pending item aside until `secs` from now
+fn snooze_review_item<R: tauri::Runtime>(app: &tauri::AppHandle<R>, id: &str, secs: u64) {
+    let mut item = {
+        let mut queue = REVIEW_QUEUE.lock().unwrap();
+        let Some(index) = queue.iter().position(|item| item.id == id) else {
+            return;
+        };
+        queue.remove(index)
+    };
+    item.snoozed_until = Some(unix_now().as_secs() + secs);
+    SNOOZED_QUEUE.lock().unwrap().push(item);
+    save_snoozed_queue();
+    publish_review_queue(app);
+}
+
+/// Returns snoozed items that are due to the pending queue and notifies
+/// about them again
+fn wake_snoozed_items<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
+    let now = unix_now().as_secs();
+    let due: Vec<ReviewItem> = {
+        let mut snoozed = SNOOZED_QUEUE.lock().unwrap();
+        let (due, waiting) = snoozed
+            .drain(..)
+            .partition(|item| item.snoozed_until.is_none_or(|at| at <= now));
+        *snoozed = waiting;
+        due
+    };
+    if due.is_empty() {
+        return;
+    }
+
+    let due: Vec<ReviewItem> = due
+        .into_iter()
+        .map(|mut item| {
+            item.snoozed_until = None;
+            item
+        })
+        .collect();
+    println!("[Lovnotifier] {} snoozed items are due", due.len());
+    REVIEW_QUEUE.lock().unwrap().extend(due.iter().cloned());
+    save_snoozed_queue();
+    publish_review_queue(app);
+    notifications::notify(app, &due);
+}
+
+/// Appends `item`, replacing any pending item for the same tmux pane
+fn insert_review_item(queue: &mut Vec<ReviewItem>, item: ReviewItem) {
+    if item.tmux_session.is_some()
+        || item.tmux_window.is_some()
+        || item.tmux_pane.is_some()
+        || item.tmux_pane_id.is_some()
+    {
+        queue.retain(|existing| !existing.same_mux_target(&item));
+    }
+    queue.push(item);
+}
+
+/// Emits the pending items in focus to the frontends, rebuilds the tray and
+/// persists the queue
+fn publish_review_queue<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
+    let queue = focus::visible(&REVIEW_QUEUE.lock().unwrap());
+    let _ = app.emit("review-queue-update", queue);
+    update_tray_menu(app);
+    save_review_queue();
+}
+
+/// Who sent a request, used for rate limiting and forwarding decisions
+struct Sender {
+    api_key: Option<String>,
+    remote: Option<std::net::SocketAddr>,
+    /// Set when another Lovnotifier instance forwarded the request to us
+    forwarded: bool,
+}
+
+impl Sender {
+    fn source_key(&self, source: Option<&str>) -> String {
+        rate_limit::source_key(source, self.api_key.as_deref(), self.remote)
+    }
+}
+
+fn sender_filter() -> impl Filter<Extract = (Sender,), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("x-api-key")
+        .and(warp::header::optional::<String>(
+            forwarding::FORWARDED_HEADER,
+        ))
+        .and(warp::addr::remote())
+        .map(|api_key, forwarded: Option<String>, remote| Sender {
+            api_key,
+            remote,
+            forwarded: forwarded.is_some(),
+        })
+}
+
+/// Runs `accept` on the blocking pool. Admitting a notification may wait
+/// for tmux (hook lookups, pane snapshots), which must not stall a worker of
+/// the HTTP server.
+async fn accept_blocking<F>(accept: F) -> Result<warp::reply::Response, warp::Rejection>
+where
+    F: FnOnce() -> warp::reply::Response + Send + 'static,
+{
+    use warp::Reply;
+
+    match tauri::async_runtime::spawn_blocking(accept).await {
+        Ok(reply) => Ok(reply),
+        Err(e) => {
+            println!("[Lovnotifier] Notification handler failed: {}", e);
+            Ok(warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({"ok": false, "error": e.to_string()})),
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+            )
+            .into_response())
+        }
+    }
+}
+
+/// Rate limits, queues and publishes a single notification
+fn accept_notification(
+    app: &tauri::AppHandle,
+    sender: &Sender,
+    payload: NotifyPayload,
+) -> warp::reply::Response {
+    use warp::Reply;
+
+    let source = sender.source_key(payload.source.as_deref());
+    if let Err(retry_after) = check_rate_limit(&source) {
+        return rate_limited_reply(&source, retry_after);
+    }
+
+    let item = match admit_notification(sender, payload) {
+        Admission::Queued(item) => item,
+        Admission::Dropped => {
+            return warp::reply::json(&serde_json::json!({"ok": true, "dropped": true}))
+                .into_response();
+        }
+        Admission::Dismissed(item) => {
+            let id = item.id.clone();
+            archive_review_items(vec![item], Outcome::AutoDismissed);
+            return warp::reply::json(
+                &serde_json::json!({"ok": true, "id": id, "dismissed": true}),
+            )
+            .into_response();
+        }
+    };
+    {
+        let mut queue = REVIEW_QUEUE.lock().unwrap();
+        insert_review_item(&mut queue, item.clone());
+    }
+    publish_review_queue(app);
+    notifications::notify(app, std::slice::from_ref(&item));
+    if !sender.forwarded {
+        forwarding::enqueue(std::slice::from_ref(&item));
+    }
+
+    warp::reply::json(&serde_json::json!({"ok": true, "id": item.id})).into_response()
+}
+
+/// Queues a batch under one lock with a single publish and a single seq
+/// write. The batch is charged one token per distinct source it names; if
+/// any of them is over its limit the whole batch is rejected with 429.
+/// Items dropped by a rule are reported as `{"dropped": true}`.
+fn accept_batch(
+    app: &tauri::AppHandle,
+    sender: &Sender,
+    payloads: Vec<NotifyPayload>,
+) -> warp::reply::Response {
+    use warp::Reply;
+
+    // Each source pays for every item it sent, and the batch is admitted
+    // only if all of them can
+    let mut per_source = std::collections::BTreeMap::<String, u32>::new();
+    for payload in &payloads {
+        *per_source
+            .entry(sender.source_key(payload.source.as_deref()))
+            .or_default() += 1;
+    }
+    let counts: Vec<(&str, u32)> = per_source
+        .iter()
+        .map(|(source, count)| (source.as_str(), *count))
+        .collect();
+    if let Err((source, retry_after)) = check_batch_rate_limit(&counts) {
+        return rate_limited_reply(&source, retry_after);
+    }
+
+    let admitted: Vec<Option<Admitted>> = payloads
+        .into_iter()
+        .map(|payload| apply_rules(sender, payload))
+        .collect();
+    let mut seqs = reserve_review_seqs(admitted.iter().flatten().count() as u64);
+
+    let mut accepted = Vec::with_capacity(admitted.len());
+    let mut dismissed = Vec::new();
+    let mut results = Vec::with_capacity(admitted.len());
+    for admitted in admitted {
+        let admission = match admitted {
+            Some(admitted) => {
+                let seq = seqs
+                    .next()
+                    .expect("a seq was reserved for every admitted item");
+                build_admitted(sender, admitted, seq)
+            }
+            None => Admission::Dropped,
+        };
+        match admission {
+            Admission::Queued(item) => {
+                results.push(serde_json::json!({"id": item.id, "seq": item.seq}));
+                accepted.push(item);
+            }
+            Admission::Dismissed(item) => {
+                results
+                    .push(serde_json::json!({"id": item.id, "seq": item.seq, "dismissed": true}));
+                dismissed.push(item);
+            }
+            Admission::Dropped => results.push(serde_json::json!({"dropped": true})),
+        }
+    }
+
+    if !dismissed.is_empty() {
+        archive_review_items(dismissed, Outcome::AutoDismissed);
+    }
+    if accepted.is_empty() {
+        return warp::reply::json(&serde_json::json!({"ok": true, "items": results}))
+            .into_response();
+    }
+
+    {
+        let mut queue = REVIEW_QUEUE.lock().unwrap();
+        for item in &accepted {
+            insert_review_item(&mut queue, item.clone());
+        }
+    }
+    publish_review_queue(app);
+    notifications::notify(app, &accepted);
+    if !sender.forwarded {
+        forwarding::enqueue(&accepted);
+    }
+
+    warp::reply::json(&serde_json::json!({"ok": true, "items": results})).into_response()
+}
+
+/// Queues a mapped webhook, or acknowledges events that map to nothing so
+/// the sender does not retry them
+fn accept_webhook(
+    app: &tauri::AppHandle,
+    sender: &Sender,
+    kind: &str,
+    payload: Option<NotifyPayload>,
+) -> warp::reply::Response {
+    use warp::Reply;
+
+    match payload {
+        Some(mut payload) => {
+            payload.source.get_or_insert_with(|| kind.to_string());
+            accept_notification(app, sender, payload)
+        }
+        None => {
+            warp::reply::json(&serde_json::json!({"ok": true, "ignored": true})).into_response()
+        }
+    }
+}
+
+fn check_rate_limit(source: &str) -> Result<(), std::time::Duration> {
+    let settings = SETTINGS.lock().unwrap().rate_limit.clone();
+    RATE_LIMITER
+        .lock()
+        .unwrap()
+        .check(source, &settings, std::time::Instant::now())
+}
+
+fn check_batch_rate_limit(counts: &[(&str, u32)]) -> Result<(), (String, std::time::Duration)> {
+    let settings = SETTINGS.lock().unwrap().rate_limit.clone();
+    RATE_LIMITER
+        .lock()
+        .unwrap()
+        .check_batch(counts, &settings, std::time::Instant::now())
+}
+
+fn rate_limited_reply(source: &str, retry_after: std::time::Duration) -> warp::reply::Response {
+    use warp::Reply;
+
+    let retry_secs = retry_after.as_secs().max(1);
+    let body = warp::reply::json(&serde_json::json!({
+        "ok": false,
+        "error": "rate limited",
+        "source": source,
+        "retry_after": retry_secs,
+    }));
+    warp::reply::with_header(
+        warp::reply::with_status(body, warp::http::StatusCode::TOO_MANY_REQUESTS),
+        "retry-after",
+        retry_secs.to_string(),
+    )
+    .into_response()
+}
+
+/// Folds notifications dropped by the rate limiter into one summary item per
+/// source, so a flood costs one queue write per flush instead of one per request
+fn flush_suppressed_notifications<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
+    let open: Vec<String> = REVIEW_QUEUE
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|item| item.id.strip_prefix("suppressed-").map(str::to_string))
+        .collect();
+    let suppressed = RATE_LIMITER
+        .lock()
+        .unwrap()
+        .take_suppressed(|source| open.iter().any(|open| open == source));
+    if suppressed.is_empty() {
+        return;
+    }
+
+    {
+        let mut queue = REVIEW_QUEUE.lock().unwrap();
+        for (source, count) in suppressed {
+            println!(
+                "[Lovnotifier] Rate limited {} notifications from {}",
+                count, source
+            );
+            let id = format!("suppressed-{}", source);
+            let title = format!("{} notifications suppressed from {}", count, source);
+            if let Some(existing) = queue.iter_mut().find(|item| item.id == id) {
+                existing.title = title;
+            } else {
+                queue.push(ReviewItem {
+                    id,
+                    seq: next_review_seq(),
+                    title,
+                    project: None,
+                    timestamp: unix_now().as_secs(),
+                    mux_kind: MuxKind::default(),
+                    tmux_session: None,
+                    tmux_window: None,
+                    tmux_pane: None,
+                    tmux_pane_id: None,
+                    tmux_socket: None,
+                    host: None,
+                    ssh_target: None,
+                    session_id: None,
+                    project_path: None,
+                    source: Some(source),
+                    link: None,
+                    priority: Priority::Low,
+                    sound: None,
+                    tags: Vec::new(),
+                    expires_at: None,
+                    snoozed_until: None,
+                    queue: None,
+                    nav_error: None,
+                    stale_since: None,
+                    snapshot: None,
+                    outcome: None,
+                });
+            }
+        }
+    }
+
+    publish_review_queue(app);
 }
 
 fn start_notify_server(app_handle: tauri::AppHandle) {
+    let app_for_housekeeping = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(HOUSEKEEPING_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            flush_suppressed_notifications(&app_for_housekeeping);
+            expire_review_items(&app_for_housekeeping);
+            wake_snoozed_items(&app_for_housekeeping);
+            dnd::refresh(&app_for_housekeeping);
+        }
+    });
+
     tauri::async_runtime::spawn(async move {
         let app_handle = Arc::new(app_handle);
 
         let app_for_notify = app_handle.clone();
         let notify_route = warp::post()
-            .and(warp::path("notify"))
+            .and(warp::path!("notify"))
+            .and(sender_filter())
+            .and(warp::header::optional::<String>(tmux_hooks::SOCKET_HEADER))
             .and(warp::body::json())
-            .map(move |payload: NotifyPayload| {
-                let app = app_for_notify.clone();
-                let item = ReviewItem {
-                    id: format!(
-                        "{}",
-                        std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_millis()
-                    ),
-                    seq: next_review_seq(),
-                    title: payload.title,
-                    project: payload.project,
-                    timestamp: std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap_or_default()
-                        .as_secs(),
-                    tmux_session: payload.tmux_session,
-                    tmux_window: payload.tmux_window,
-                    tmux_pane: payload.tmux_pane,
-                    session_id: payload.session_id,
-                    project_path: payload.project_path,
-                };
-
-                {
-                    let mut queue = REVIEW_QUEUE.lock().unwrap();
-                    if item.tmux_session.is_some()
-                        || item.tmux_window.is_some()
-                        || item.tmux_pane.is_some()
-                    {
-                        queue.retain(|existing| {
-                            existing.tmux_session != item.tmux_session
-                                || existing.tmux_window != item.tmux_window
-                                || existing.tmux_pane != item.tmux_pane
-                        });
+            .and_then(
+                move |sender: Sender, socket: Option<String>, mut payload: NotifyPayload| {
+                    // tmux hooks send their server's socket path as a header
+                    if payload.tmux_socket.is_none() {
+                        payload.tmux_socket = socket.filter(|s| !s.is_empty());
                     }
-                    queue.push(item.clone());
-                }
+                    let app = app_for_notify.clone();
+                    accept_blocking(move || accept_notification(&app, &sender, payload))
+                },
+            );
+
+        let app_for_batch = app_handle.clone();
+        let batch_route = warp::post()
+            .and(warp::path!("notify" / "batch"))
+            .and(sender_filter())
+            .and(warp::body::json())
+            .and_then(move |sender: Sender, payloads: Vec<NotifyPayload>| {
+                let app = app_for_batch.clone();
+                accept_blocking(move || accept_batch(&app, &sender, payloads))
+            });
 
-                let queue = REVIEW_QUEUE.lock().unwrap().clone();
-                let _ = app.emit("review-queue-update", queue);
-                update_tray_menu(&app);
-                save_review_queue();
+        let app_for_github = app_handle.clone();
+        let github_route = warp::post()
+            .and(warp::path!("hooks" / "github"))
+            .and(sender_filter())
+            .and(warp::header::optional::<String>("x-github-event"))
+            .and(warp::header::optional::<String>("x-gitea-event"))
+            .and(warp::body::json())
+            .and_then(
+                move |sender: Sender,
+                      github: Option<String>,
+                      gitea: Option<String>,
+                      body: serde_json::Value| {
+                    // Gitea sends both headers; prefer its own name for the source
+                    let kind = if gitea.is_some() { "gitea" } else { "github" };
+                    let event = gitea.or(github).unwrap_or_default();
+                    let payload = webhooks::from_github(&event, &body);
+                    let app = app_for_github.clone();
+                    accept_blocking(move || accept_webhook(&app, &sender, kind, payload))
+                },
+            );
+
+        let app_for_gitlab = app_handle.clone();
+        let gitlab_route = warp::post()
+            .and(warp::path!("hooks" / "gitlab"))
+            .and(sender_filter())
+            .and(warp::body::json())
+            .and_then(move |sender: Sender, body: serde_json::Value| {
+                let payload = webhooks::from_gitlab(&body);
+                let app = app_for_gitlab.clone();
+                accept_blocking(move || accept_webhook(&app, &sender, "gitlab", payload))
+            });
 
-                warp::reply::json(&serde_json::json!({"ok": true, "id": item.id}))
+        let app_for_generic = app_handle.clone();
+        let generic_route = warp::post()
+            .and(warp::path!("hooks" / "generic"))
+            .and(sender_filter())
+            .and(warp::query::<std::collections::HashMap<String, String>>())
+            .and(warp::body::json())
+            .and_then(move |sender: Sender, query, body: serde_json::Value| {
+                let payload = webhooks::from_generic(&query, &body);
+                let app = app_for_generic.clone();
+                accept_blocking(move || accept_webhook(&app, &sender, "generic", payload))
             });
 
-        let queue_route = warp::get().and(warp::path("queue")).map(|| {
-            let queue = REVIEW_QUEUE.lock().unwrap().clone();
-            warp::reply::json(&queue)
-        });
+        let queue_route = warp::get()
+            .and(warp::path!("queue"))
+            .and(warp::query::<std::collections::HashMap<String, String>>())
+            .map(|query: std::collections::HashMap<String, String>| {
+                let mut queue = REVIEW_QUEUE.lock().unwrap().clone();
+                if let Some(name) = query.get("queue") {
+                    queue.retain(|item| item.queue.as_ref() == Some(name));
+                }
+                warp::reply::json(&queue)
+            });
+
+        let item_route = warp::get()
+            .and(warp::path!("queue" / String))
+            .map(|id: String| {
+                use warp::Reply;
+
+                let pending = REVIEW_QUEUE.lock().unwrap();
+                let completed = COMPLETED_QUEUE.lock().unwrap();
+                match pending
+                    .iter()
+                    .chain(completed.iter().rev())
+                    .find(|item| item.id == id)
+                {
+                    Some(item) => warp::reply::json(item).into_response(),
+                    None => warp::reply::with_status(
+                        warp::reply::json(&serde_json::json!({"ok": false, "error": "not found"})),
+                        warp::http::StatusCode::NOT_FOUND,
+                    )
+                    .into_response(),
+                }
+            });
 
         let dismiss_route = warp::delete()
             .and(warp::path("queue"))
@@ -441,7 +1407,14 @@ fn start_notify_server(app_handle: tauri::AppHandle) {
                 warp::reply::json(&serde_json::json!({"ok": true}))
             });
 
-        let routes = notify_route.or(queue_route).or(dismiss_route);
+        let routes = notify_route
+            .or(batch_route)
+            .or(github_route)
+            .or(gitlab_route)
+            .or(generic_route)
+            .or(queue_route)
+            .or(item_route)
+            .or(dismiss_route);
 
         println!(
             "[Lovnotifier] Notification server starting on port {}",
@@ -457,61 +1430,37 @@ fn start_notify_server(app_handle: tauri::AppHandle) {
 // Tray Menu
 // ============================================================================
 
-fn build_tray_menu<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Result<Menu<R>, tauri::Error> {
-    use tauri::menu::{MenuBuilder, MenuItemBuilder};
-
-    let queue = REVIEW_QUEUE.lock().unwrap();
-    let mut menu_builder = MenuBuilder::new(app);
-
-    if queue.is_empty() {
-        let empty_item = MenuItemBuilder::with_id("empty", "No messages")
-            .enabled(false)
-            .build(app)?;
-        menu_builder = menu_builder.item(&empty_item);
-    } else {
-        let mut sorted: Vec<_> = queue.iter().collect();
-        sorted.sort_by_key(|item| item.timestamp);
-
-        for item in sorted.iter().take(10) {
-            let label = format!("#{} {}", item.seq, truncate_str(&item.title, 30));
-            let menu_item =
-                MenuItemBuilder::with_id(format!("msg:{}", item.id), label).build(app)?;
-            menu_builder = menu_builder.item(&menu_item);
-        }
+/// Switches to the item's pane, if it has one. On failure the error is
+/// recorded on the item and returned.
+fn navigate_review_item<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    item: &ReviewItem,
+) -> Result<(), String> {
+    let Some(target) = item.mux_target() else {
+        return Ok(());
+    };
+    let result = navigate_to_target(&target);
+    let Some(error) = result.error_message() else {
+        return Ok(());
+    };
 
-        if queue.len() > 10 {
-            let more_item =
-                MenuItemBuilder::with_id("more", format!("... and {} more", queue.len() - 10))
-                    .enabled(false)
-                    .build(app)?;
-            menu_builder = menu_builder.item(&more_item);
+    // Keep the item so the user can see why and retry or dismiss it
+    {
+        let mut queue = REVIEW_QUEUE.lock().unwrap();
+        if let Some(pending) = queue.iter_mut().find(|i| i.id == item.id) {
+            pending.nav_error = Some(error.clone());
         }
     }
-
-    menu_builder = menu_builder.separator();
-
-    let toggle_float =
-        MenuItemBuilder::with_id("tray_toggle_float", "Toggle Float Window").build(app)?;
-    let settings_item = MenuItemBuilder::with_id("tray_settings", "Settings...").build(app)?;
-    let quit_item = MenuItemBuilder::with_id("tray_quit", "Quit").build(app)?;
-
-    menu_builder
-        .item(&toggle_float)
-        .item(&settings_item)
-        .separator()
-        .item(&quit_item)
-        .build()
-}
-
-fn truncate_str(s: &str, max_len: usize) -> String {
-    if s.chars().count() <= max_len {
-        s.to_string()
-    } else {
-        let truncated: String = s.chars().take(max_len - 1).collect();
-        format!("{}…", truncated)
-    }
+    let _ = app.emit(
+        "navigation-failed",
+        serde_json::json!({"id": item.id, "result": result}),
+    );
+    publish_review_queue(app);
+    Err(error)
 }
 
+/// Navigates to the item on a worker thread and completes it on success.
+/// Menu, shortcut and notification handlers run on the main thread.
 fn consume_review_item<R: tauri::Runtime>(app: &tauri::AppHandle<R>, msg_id: &str) {
     let item = {
         let queue = REVIEW_QUEUE.lock().unwrap();
@@ -519,39 +1468,182 @@ fn consume_review_item<R: tauri::Runtime>(app: &tauri::AppHandle<R>, msg_id: &st
     };
 
     if let Some(item) = item {
-        if let (Some(session), Some(window), Some(pane)) =
-            (&item.tmux_session, &item.tmux_window, &item.tmux_pane)
-        {
-            let _ = navigate_to_tmux_pane(session.clone(), window.clone(), pane.clone());
-        }
+        let app = app.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            if navigate_review_item(&app, &item).is_ok() {
+                complete_review_items(&app, &[item.id], Outcome::Navigated);
+            }
+        });
+    }
+}
+
+/// Shows the float window, creating it if it was closed
+fn show_float_window<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
+    use tauri::{WebviewUrl, WebviewWindowBuilder};
 
+    if let Some(window) = app.get_webview_window("float") {
+        float_position::restore(&window);
+        let _ = window.show();
+    } else {
+        // Built hidden where it belongs; `restore` only corrects rounding
+        // across monitors with different scale factors before it is shown
+        let (x, y) = float_position::initial_position(app);
+        let (width, height) = float_position::SIZE;
+        if let Ok(window) =
+            WebviewWindowBuilder::new(app, "float", WebviewUrl::App("/float.html".into()))
+                .title("")
+                .inner_size(width, height)
+                .position(x, y)
+                .decorations(false)
+                .transparent(true)
+                .always_on_top(true)
+                .skip_taskbar(true)
+                .resizable(false)
+                .visible(false)
+                .focused(false)
+                .build()
         {
-            let mut queue = REVIEW_QUEUE.lock().unwrap();
-            if let Some(pos) = queue.iter().position(|i| i.id == msg_id) {
-                let removed = queue.remove(pos);
-                persist_completed_item(&removed);
-
-                let mut completed = COMPLETED_QUEUE.lock().unwrap();
-                completed.insert(0, removed);
-                completed.truncate(100);
-            }
+            float_position::track(&window);
+            float_position::restore(&window);
+            let _ = window.show();
         }
+    }
+}
 
-        let current_queue: Vec<ReviewItem> = REVIEW_QUEUE.lock().unwrap().clone();
-        let _ = app.emit("review-queue-update", current_queue);
-        update_tray_menu(app);
-        save_review_queue();
+fn toggle_float_window<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
+    match app.get_webview_window("float") {
+        Some(window) if window.is_visible().unwrap_or(false) => {
+            let _ = window.hide();
+        }
+        _ => show_float_window(app),
     }
 }
 
+/// Moves the float window back to its default position on this monitor setup
+#[tauri::command]
+fn reset_float_window_position(app: tauri::AppHandle) -> Result<(), String> {
+    float_position::reset(&app)
+}
+
+/// Rebuilds the tray from a copy of the queue on the main thread, where menu
+/// items are created without waiting, and only when what it shows changed
 fn update_tray_menu<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
-    if let Some(tray) = app.tray_by_id("main-tray") {
-        if let Ok(menu) = build_tray_menu(app) {
+    // With `menu_bar` off there is nothing to rebuild; `create_tray` records
+    // the state it starts from when the tray comes back
+    if app.tray_by_id("main-tray").is_none() {
+        return;
+    }
+    let queue = REVIEW_QUEUE.lock().unwrap().clone();
+    let settings = current_settings();
+    if !tray::menu_changed(&queue, &settings) {
+        return;
+    }
+    let handle = app.clone();
+    let _ = app.run_on_main_thread(move || {
+        let Some(tray) = handle.tray_by_id("main-tray") else {
+            return;
+        };
+        if let Ok(menu) = tray::build_menu(&handle, &queue, &settings) {
             let _ = tray.set_menu(Some(menu));
         }
-        let count = REVIEW_QUEUE.lock().unwrap().len();
-        let _ = tray.set_title(Some(count.to_string()));
+        let visible: Vec<ReviewItem> = queue
+            .into_iter()
+            .filter(|item| settings.focus.matches(item))
+            .collect();
+        if let Some((icon, is_template)) = tray_icon::icon_for(&visible) {
+            let _ = tray.set_icon(Some(icon));
+            let _ = tray.set_icon_as_template(is_template);
+        }
+        if let Some(title) = tray_icon::title_for(&visible) {
+            let _ = tray.set_title(Some(title));
+        }
+        let _ = tray.set_tooltip(Some(dnd::tooltip(&settings.dnd)));
+    });
+}
+
+fn show_settings_window<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
+    // Pre-configured in tauri.conf.json
+    if let Some(window) = app.get_webview_window("settings") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn create_tray<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<()> {
+    let queue = REVIEW_QUEUE.lock().unwrap().clone();
+    let settings = current_settings();
+    tray::remember_menu(&queue, &settings);
+    let tray_menu = tray::build_menu(app, &queue, &settings)?;
+    println!("[Lovnotifier] Tray init: queue has {} messages", queue.len());
+
+    // Badged with the pending count, or a template image titled with it on macOS
+    let visible: Vec<ReviewItem> = queue
+        .into_iter()
+        .filter(|item| settings.focus.matches(item))
+        .collect();
+    let (tray_icon, is_template) = tray_icon::icon_for(&visible)
+        .unwrap_or_else(|| (app.default_window_icon().unwrap().clone(), false));
+
+    let tray = TrayIconBuilder::with_id("main-tray")
+        .icon(tray_icon)
+        .icon_as_template(is_template)
+        .menu(&tray_menu)
+        .show_menu_on_left_click(true)
+        .tooltip(dnd::tooltip(&settings.dnd))
+        .on_menu_event(|app, event| {
+            let id = event.id.as_ref();
+            if let Some((action, msg_id)) = tray::parse_item_event(id) {
+                tray::run_item_action(app, action, msg_id);
+            } else if let Some(action) = id.strip_prefix("focus:") {
+                focus::run_menu_action(app, action);
+            } else if id == "tray_dnd" {
+                dnd::toggle(app);
+            } else if id == "tray_toggle_float" {
+                toggle_float_window(app);
+            } else if id == "tray_settings" {
+                show_settings_window(app);
+            } else if id == "tray_quit" {
+                std::process::exit(0);
+            }
+        })
+        .build(app)?;
+    if let Some(title) = tray_icon::title_for(&visible) {
+        tray.set_title(Some(title))?;
+    }
+    Ok(())
+}
+
+/// Brings the tray icon and float window in line with `settings`. On save,
+/// `previous` holds the old settings; the float window is only shown or
+/// hidden when its flag changed, so saving does not undo the tray toggle.
+fn apply_settings<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    previous: Option<&NotifierSettings>,
+    settings: &NotifierSettings,
+) -> tauri::Result<()> {
+    let has_tray = app.tray_by_id("main-tray").is_some();
+    if settings.menu_bar && !has_tray {
+        create_tray(app)?;
+    } else if !settings.menu_bar && has_tray {
+        println!("[Lovnotifier] Removing tray icon");
+        app.remove_tray_by_id("main-tray");
+    }
+
+    if previous.is_none_or(|previous| previous.float_window != settings.float_window) {
+        if settings.float_window {
+            show_float_window(app);
+        } else if let Some(window) = app.get_webview_window("float") {
+            let _ = window.hide();
+        }
     }
+
+    // Picks up a DND toggle, and re-sends the queue and rebuilds the tray for
+    // the focus and grouping
+    dnd::refresh(app);
+    publish_review_queue(app);
+
+    let _ = app.emit("settings-changed", settings);
+    Ok(())
 }
 
 // ============================================================================
@@ -727,6 +1819,8 @@ fn set_cursor(cursor_type: String) {
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
         .setup(|app| {
             // Migrate data from Lovcode if needed
             migrate_from_lovcode();
@@ -734,127 +1828,55 @@ pub fn run() {
             // Load persisted data
             load_review_seq();
             load_review_queue();
+            load_snoozed_queue();
             load_completed_queue();
 
             // Start notification HTTP server
             start_notify_server(app.handle().clone());
 
+            // Deliver pending forwards, including any left over from last run
+            forwarding::start_worker();
+            tmux_watch::start(app.handle().clone());
+
             // Configure float window for macOS
             #[cfg(target_os = "macos")]
             setup_float_window_macos(app);
 
-            // Register global shortcut F4
+            // Remember where the float window is placed on each monitor setup
+            if let Some(window) = app.get_webview_window("float") {
+                float_position::track(&window);
+            }
+
+            // Register the global shortcuts from settings (F4 by default)
             #[cfg(desktop)]
             {
-                use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Shortcut, ShortcutState};
-
-                let f4_shortcut = Shortcut::new(None, Code::F4);
-                let shortcut_app = app.handle().clone();
-
-                app.handle().plugin(
-                    tauri_plugin_global_shortcut::Builder::new()
-                        .with_handler(move |_app, shortcut, event| {
-                            if shortcut == &f4_shortcut && event.state() == ShortcutState::Pressed {
-                                let oldest_id = {
-                                    let queue = REVIEW_QUEUE.lock().unwrap();
-                                    queue
-                                        .iter()
-                                        .min_by_key(|item| item.timestamp)
-                                        .map(|item| item.id.clone())
-                                };
-                                if let Some(id) = oldest_id {
-                                    consume_review_item(&shortcut_app, &id);
-                                }
-                            }
-                        })
-                        .build(),
-                )?;
-
-                app.global_shortcut().register(f4_shortcut)?;
+                app.handle().plugin(shortcuts::plugin())?;
+                if let Err(e) = shortcuts::apply(app.handle(), &current_settings()) {
+                    println!("[Lovnotifier] {}", e);
+                }
             }
 
-            // Create system tray
-            let app_handle = app.handle();
-            let initial_count = REVIEW_QUEUE.lock().unwrap().len();
-            let tray_menu = build_tray_menu(app_handle)?;
-            println!("[Lovnotifier] Tray init: queue has {} messages", initial_count);
-
-            // Load tray icon (template image for macOS)
-            let tray_icon_bytes = include_bytes!("../icons/tray-icon.png");
-            let tray_icon = image::load_from_memory(tray_icon_bytes)
-                .map(|img| {
-                    let rgba = img.to_rgba8();
-                    let (width, height) = rgba.dimensions();
-                    tauri::image::Image::new_owned(rgba.into_raw(), width, height)
-                })
-                .unwrap_or_else(|_| app.default_window_icon().unwrap().clone());
-
-            let _tray = TrayIconBuilder::with_id("main-tray")
-                .icon(tray_icon)
-                .icon_as_template(true)
-                .menu(&tray_menu)
-                .show_menu_on_left_click(true)
-                .tooltip("Lovnotifier")
-                .title(initial_count.to_string())
-                .on_menu_event(|app, event| {
-                    use tauri::WebviewWindowBuilder;
-                    use tauri::WebviewUrl;
-
-                    let id = event.id.as_ref();
-                    if id.starts_with("msg:") {
-                        let msg_id = &id[4..];
-                        consume_review_item(app, msg_id);
-                    } else if id == "tray_toggle_float" {
-                        if let Some(window) = app.get_webview_window("float") {
-                            if window.is_visible().unwrap_or(false) {
-                                let _ = window.hide();
-                            } else {
-                                let _ = window.show();
-                            }
-                        } else {
-                            if let Ok(window) = WebviewWindowBuilder::new(
-                                app,
-                                "float",
-                                WebviewUrl::App("/float.html".into()),
-                            )
-                            .title("")
-                            .inner_size(121.0, 48.0)
-                            .position(100.0, 100.0)
-                            .decorations(false)
-                            .transparent(true)
-                            .always_on_top(true)
-                            .skip_taskbar(true)
-                            .resizable(false)
-                            .visible(true)
-                            .focused(false)
-                            .build()
-                            {
-                                let _ = window.show();
-                            }
-                        }
-                    } else if id == "tray_settings" {
-                        // Open settings window (pre-configured in tauri.conf.json)
-                        if let Some(window) = app.get_webview_window("settings") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    } else if id == "tray_quit" {
-                        std::process::exit(0);
-                    }
-                })
-                .build(app)?;
+            // Show the tray icon and float window as configured
+            apply_settings(app.handle(), None, &current_settings())?;
 
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             get_settings,
             save_settings,
+            get_dnd_active,
+            set_focus_mode,
             emit_review_queue,
             get_review_queue,
             get_completed_queue,
             dismiss_review_item,
             clear_completed_queue,
+            test_notification_rules,
             navigate_to_tmux_pane,
+            open_review_item,
+            install_tmux_hooks,
+            uninstall_tmux_hooks,
+            reset_float_window_position,
             get_cursor_position_in_window,
             get_cursor_position,
             set_cursor,
@@ -0,0 +1,332 @@
+use crate::{Priority, ReviewItem};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct NotificationSettings {
+    /// Items below this priority are queued without a native notification
+    pub min_priority: Priority,
+    /// Sound for high and urgent items that do not name one
+    pub sound: Option<String>,
+    /// A batch with more items than this raises one summary notification
+    pub batch_summary_over: usize,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            min_priority: Priority::Normal,
+            sound: None,
+            batch_summary_over: 3,
+        }
+    }
+}
+
+/// What a native notification shows and what clicking it does
+struct DesktopNotification {
+    title: String,
+    body: String,
+    priority: Priority,
+    sound: Option<String>,
+    /// Item consumed on click; clicking a batch summary shows the float
+    /// window instead
+    item_id: Option<String>,
+}
+
+/// Project and terminal location, then the last line of the pane snapshot
+pub fn body(item: &ReviewItem) -> String {
+    let mut parts = Vec::new();
+    if let Some(project) = &item.project {
+        parts.push(project.clone());
+    }
+    if let Some(session) = &item.tmux_session {
+        let mut location = session.clone();
+        if let Some(window) = &item.tmux_window {
+            location = format!("{}:{}", location, window);
+        }
+        if let Some(host) = &item.host {
+            location = format!("{} on {}", location, host);
+        }
+        parts.push(location);
+    }
+    let mut body = parts.join(" · ");
+    let last_line = item
+        .snapshot
+        .as_deref()
+        .and_then(|snapshot| snapshot.lines().rev().find(|line| !line.trim().is_empty()));
+    if let Some(line) = last_line {
+        if !body.is_empty() {
+            body.push('\n');
+        }
+        body.push_str(line.trim());
+    }
+    body
+}
+
+fn sound_for(item: &ReviewItem, settings: &NotificationSettings) -> Option<String> {
+    item.sound.clone().or_else(|| {
+        (item.priority >= Priority::High)
+            .then(|| settings.sound.clone())
+            .flatten()
+    })
+}
+
+/// One notification standing for several items; clicking it shows the
+/// float window
+fn summary(title: String, items: &[&ReviewItem], sound: Option<String>) -> DesktopNotification {
+    let titles: Vec<&str> = items
+        .iter()
+        .take(3)
+        .map(|item| item.title.as_str())
+        .collect();
+    DesktopNotification {
+        title,
+        body: titles.join("\n"),
+        priority: items
+            .iter()
+            .map(|item| item.priority)
+            .max()
+            .unwrap_or_default(),
+        sound,
+        item_id: None,
+    }
+}
+
+/// Raises native notifications for newly queued items, if enabled and not
+/// held back by do-not-disturb. Items outside focus mode stay silent.
+pub fn notify<R: Runtime>(app: &AppHandle<R>, items: &[ReviewItem]) {
+    let settings = crate::current_settings();
+    if !settings.notify {
+        return;
+    }
+    let config = settings.notifications;
+    let due: Vec<&ReviewItem> = items
+        .iter()
+        .filter(|item| item.priority >= config.min_priority && settings.focus.matches(item))
+        .collect();
+    let due = crate::dnd::hold(&settings.dnd, due);
+    if due.is_empty() {
+        return;
+    }
+
+    if due.len() > config.batch_summary_over.max(1) {
+        let sound = due.iter().find_map(|item| sound_for(item, &config));
+        show(
+            app,
+            summary(format!("{} new notifications", due.len()), &due, sound),
+        );
+        return;
+    }
+    for item in due {
+        show(
+            app,
+            DesktopNotification {
+                title: item.title.clone(),
+                body: body(item),
+                priority: item.priority,
+                sound: sound_for(item, &config),
+                item_id: Some(item.id.clone()),
+            },
+        );
+    }
+}
+
+/// Summarizes the items whose notifications do-not-disturb held back, without
+/// a sound
+pub fn notify_held<R: Runtime>(app: &AppHandle<R>, items: &[ReviewItem]) {
+    if !crate::current_settings().notify || items.is_empty() {
+        return;
+    }
+    let title = match items.len() {
+        1 => "1 notification arrived during Do Not Disturb".to_string(),
+        count => format!("{} notifications arrived during Do Not Disturb", count),
+    };
+    let items: Vec<&ReviewItem> = items.iter().collect();
+    show(app, summary(title, &items, None));
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos", windows))]
+fn on_click<R: Runtime>(app: &AppHandle<R>, item_id: Option<&str>) {
+    match item_id {
+        // Focus mode may have changed since the notification was shown
+        Some(id) if !in_focus(id) => {
+            println!("[Lovnotifier] Ignoring click on {} outside focus", id)
+        }
+        // A no-op if the item was completed in the meantime
+        Some(id) => crate::consume_review_item(app, id),
+        None => crate::show_float_window(app),
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos", windows))]
+fn in_focus(id: &str) -> bool {
+    let focus = crate::current_settings().focus;
+    crate::REVIEW_QUEUE
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|item| item.id == id)
+        .is_none_or(|item| focus.matches(item))
+}
+
+/// Posts over one long-lived D-Bus connection, started on first use
+#[cfg(target_os = "linux")]
+static NOTIFIER: std::sync::OnceLock<Option<crate::freedesktop::Notifier>> =
+    std::sync::OnceLock::new();
+
+/// Linux: the freedesktop Notifications D-Bus interface directly, as the
+/// Tauri plugin does not report clicks
+#[cfg(target_os = "linux")]
+fn show<R: Runtime>(app: &AppHandle<R>, notification: DesktopNotification) {
+    use crate::freedesktop::{Notification, Notifier, Urgency};
+
+    let notifier = NOTIFIER.get_or_init(|| {
+        let app = app.clone();
+        zbus::blocking::Connection::session()
+            .and_then(|connection| {
+                Notifier::start(connection, move |item_id| {
+                    on_click(&app, item_id.as_deref())
+                })
+            })
+            .inspect_err(|e| println!("[Lovnotifier] Native notifications unavailable: {}", e))
+            .ok()
+    });
+    let Some(notifier) = notifier else {
+        return;
+    };
+    notifier.show(Notification {
+        summary: notification.title,
+        body: notification.body,
+        urgency: match notification.priority {
+            Priority::Low => Urgency::Low,
+            Priority::Normal | Priority::High => Urgency::Normal,
+            Priority::Urgent => Urgency::Critical,
+        },
+        sound: notification.sound,
+        // Urgent items stay on screen until acted on
+        persistent: notification.priority == Priority::Urgent,
+        tag: notification.item_id,
+    });
+}
+
+/// Most notifications waiting for a click at once, one thread each
+#[cfg(target_os = "macos")]
+const MAX_CLICK_WAITERS: usize = 16;
+
+#[cfg(target_os = "macos")]
+static CLICK_WAITERS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// macOS: the user notification center directly, as the Tauri plugin does not
+/// report clicks. A notification's thread waits until it is clicked or
+/// cleared; past `MAX_CLICK_WAITERS` of them, further ones are posted
+/// without waiting and clicking them only brings Lovnotifier forward.
+#[cfg(target_os = "macos")]
+fn show<R: Runtime>(app: &AppHandle<R>, notification: DesktopNotification) {
+    use mac_notification_sys::NotificationResponse;
+    use std::sync::atomic::Ordering;
+    use tauri::Manager;
+
+    static APPLICATION: std::sync::Once = std::sync::Once::new();
+    APPLICATION.call_once(|| {
+        // Development builds are not bundled, so macOS does not know their id
+        let bundle = if tauri::is_dev() {
+            "com.apple.Terminal".to_string()
+        } else {
+            app.config().identifier.clone()
+        };
+        if let Err(e) = mac_notification_sys::set_application(&bundle) {
+            println!(
+                "[Lovnotifier] Cannot post notifications as {}: {}",
+                bundle, e
+            );
+        }
+    });
+
+    let wait = CLICK_WAITERS
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |waiters| {
+            (waiters < MAX_CLICK_WAITERS).then_some(waiters + 1)
+        })
+        .is_ok();
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut options = mac_notification_sys::Notification::new();
+        options
+            .maybe_sound(notification.sound.as_deref())
+            .wait_for_click(wait)
+            .asynchronous(!wait);
+        let response = mac_notification_sys::send_notification(
+            &notification.title,
+            None,
+            &notification.body,
+            Some(&options),
+        );
+        if wait {
+            CLICK_WAITERS.fetch_sub(1, Ordering::SeqCst);
+        }
+        match response {
+            Ok(NotificationResponse::Click) => on_click(&app, notification.item_id.as_deref()),
+            Ok(_) => {}
+            Err(e) => println!("[Lovnotifier] Native notification failed: {}", e),
+        }
+    });
+}
+
+/// Windows: a toast through WinRT directly, as the Tauri plugin does not
+/// report clicks. Activation arrives on a WinRT thread.
+#[cfg(windows)]
+fn show<R: Runtime>(app: &AppHandle<R>, notification: DesktopNotification) {
+    use tauri::Manager;
+    use tauri_winrt_notification::{Duration, Sound, Toast};
+
+    // Only an installed app has its own AppUserModelID; like the Tauri
+    // plugin, builds run from `target` borrow PowerShell's
+    let installed = tauri::utils::platform::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.to_path_buf()))
+        .is_some_and(|dir| !dir.ends_with("target/debug") && !dir.ends_with("target/release"));
+    let app_id = if installed {
+        app.config().identifier.clone()
+    } else {
+        Toast::POWERSHELL_APP_ID.to_string()
+    };
+
+    let mut toast = Toast::new(&app_id)
+        .title(&notification.title)
+        .text1(&notification.body)
+        // Urgent items stay on screen longer
+        .duration(match notification.priority {
+            Priority::Urgent => Duration::Long,
+            _ => Duration::Short,
+        });
+    if let Some(sound) = &notification.sound {
+        toast = toast.sound(Some(sound.parse().unwrap_or(Sound::Default)));
+    }
+    let app = app.clone();
+    let item_id = notification.item_id;
+    let toast = toast.on_activated(move |_| {
+        on_click(&app, item_id.as_deref());
+        Ok(())
+    });
+    if let Err(e) = toast.show() {
+        println!("[Lovnotifier] Native notification failed: {}", e);
+    }
+}
+
+/// Elsewhere the Tauri plugin shows the notification; clicks are not reported
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn show<R: Runtime>(app: &AppHandle<R>, notification: DesktopNotification) {
+    use tauri_plugin_notification::NotificationExt;
+
+    let mut builder = app
+        .notification()
+        .builder()
+        .title(&notification.title)
+        .body(&notification.body);
+    if let Some(sound) = notification.sound {
+        builder = builder.sound(sound);
+    }
+    if let Err(e) = builder.show() {
+        println!("[Lovnotifier] Native notification failed: {}", e);
+    }
+}
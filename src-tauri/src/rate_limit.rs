@@ -0,0 +1,368 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// Buckets untouched for this long are dropped, which also resets their
+// suppressed total so the next flood starts counting from zero
+const BUCKET_IDLE_TTL: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct RateLimitSettings {
+    pub enabled: bool,
+    /// Notifications a single source may send back-to-back
+    pub burst: u32,
+    /// Sustained notifications per minute once the burst is used up
+    pub per_minute: u32,
+}
+
+impl Default for RateLimitSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            burst: 20,
+            per_minute: 60,
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+    last_seen: Instant,
+    suppressed_pending: u64,
+    suppressed_total: u64,
+}
+
+/// Token bucket limiter keyed by notification source
+#[derive(Default)]
+pub struct RateLimiter {
+    buckets: HashMap<String, Bucket>,
+}
+
+impl RateLimiter {
+    /// Takes a token for `source`, or returns how long until one is available
+    pub fn check(
+        &mut self,
+        source: &str,
+        settings: &RateLimitSettings,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        self.check_batch(&[(source, 1)], settings, now)
+            .map_err(|(_, wait)| wait)
+    }
+
+    /// Takes one token per notification from each `(source, count)`, for all
+    /// of them or none. Otherwise every notification counts as suppressed,
+    /// and the first source over its limit is returned with how long until
+    /// it has enough tokens. A batch larger than the burst is let through
+    /// once the bucket is full and leaves it in debt, so it still costs its
+    /// full count at the sustained rate.
+    pub fn check_batch(
+        &mut self,
+        counts: &[(&str, u32)],
+        settings: &RateLimitSettings,
+        now: Instant,
+    ) -> Result<(), (String, Duration)> {
+        if !settings.enabled {
+            return Ok(());
+        }
+
+        let burst = settings.burst.max(1) as f64;
+        let rate_per_sec = settings.per_minute.max(1) as f64 / 60.0;
+
+        self.buckets
+            .retain(|_, b| now.duration_since(b.last_seen) < BUCKET_IDLE_TTL);
+
+        for (source, _) in counts {
+            let bucket = self
+                .buckets
+                .entry(source.to_string())
+                .or_insert_with(|| Bucket {
+                    tokens: burst,
+                    last_refill: now,
+                    last_seen: now,
+                    suppressed_pending: 0,
+                    suppressed_total: 0,
+                });
+
+            let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * rate_per_sec).min(burst);
+            bucket.last_refill = now;
+            bucket.last_seen = now;
+        }
+
+        let over_limit = counts.iter().find_map(|(source, count)| {
+            let needed = (*count as f64).min(burst);
+            let tokens = self.buckets[*source].tokens;
+            (tokens < needed).then(|| {
+                let wait = (needed - tokens) / rate_per_sec;
+                (source.to_string(), Duration::from_secs_f64(wait))
+            })
+        });
+
+        for (source, count) in counts {
+            let bucket = self.buckets.get_mut(*source).unwrap();
+            if over_limit.is_some() {
+                bucket.suppressed_pending += *count as u64;
+                bucket.suppressed_total += *count as u64;
+            } else {
+                bucket.tokens -= *count as f64;
+            }
+        }
+        over_limit.map_or(Ok(()), Err)
+    }
+
+    /// Returns `(source, suppressed)` for every source that dropped
+    /// notifications since the last call. The count keeps adding up while
+    /// `is_open` reports the source's summary as still pending; once it was
+    /// dismissed, counting restarts with this flush.
+    pub fn take_suppressed(&mut self, is_open: impl Fn(&str) -> bool) -> Vec<(String, u64)> {
+        let mut result = Vec::new();
+        for (source, bucket) in self.buckets.iter_mut() {
+            if bucket.suppressed_pending > 0 {
+                if !is_open(source) {
+                    bucket.suppressed_total = bucket.suppressed_pending;
+                }
+                bucket.suppressed_pending = 0;
+                result.push((source.clone(), bucket.suppressed_total));
+            }
+        }
+        result.sort();
+        result
+    }
+}
+
+/// Picks the rate limit key: explicit source, then API key, then remote IP
+pub fn source_key(
+    source: Option<&str>,
+    api_key: Option<&str>,
+    remote: Option<std::net::SocketAddr>,
+) -> String {
+    if let Some(source) = source.filter(|s| !s.is_empty()) {
+        return source.to_string();
+    }
+    if let Some(key) = api_key.filter(|k| !k.is_empty()) {
+        // Never echo the key itself into queue items or logs
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        key.hash(&mut hasher);
+        return format!("key:{:08x}", hasher.finish() as u32);
+    }
+    match remote {
+        Some(addr) => addr.ip().to_string(),
+        None => "unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(burst: u32, per_minute: u32) -> RateLimitSettings {
+        RateLimitSettings {
+            enabled: true,
+            burst,
+            per_minute,
+        }
+    }
+
+    #[test]
+    fn allows_the_burst_then_refills_at_the_sustained_rate() {
+        let mut limiter = RateLimiter::default();
+        let settings = settings(3, 60);
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.check("ci", &settings, start).is_ok());
+        }
+        let wait = limiter.check("ci", &settings, start).unwrap_err();
+        assert_eq!(wait, Duration::from_secs(1));
+
+        // 60 per minute refills one token a second
+        assert!(limiter
+            .check("ci", &settings, start + Duration::from_secs(1))
+            .is_ok());
+        assert!(limiter
+            .check("ci", &settings, start + Duration::from_secs(1))
+            .is_err());
+    }
+
+    #[test]
+    fn refill_never_exceeds_the_burst() {
+        let mut limiter = RateLimiter::default();
+        let settings = settings(2, 60);
+        let start = Instant::now();
+
+        assert!(limiter.check("ci", &settings, start).is_ok());
+        let later = start + Duration::from_secs(300);
+        assert!(limiter.check("ci", &settings, later).is_ok());
+        assert!(limiter.check("ci", &settings, later).is_ok());
+        assert!(limiter.check("ci", &settings, later).is_err());
+    }
+
+    #[test]
+    fn sources_have_separate_buckets() {
+        let mut limiter = RateLimiter::default();
+        let settings = settings(1, 60);
+        let now = Instant::now();
+
+        assert!(limiter.check("ci", &settings, now).is_ok());
+        assert!(limiter.check("ci", &settings, now).is_err());
+        assert!(limiter.check("deploy", &settings, now).is_ok());
+    }
+
+    #[test]
+    fn disabled_limiter_allows_everything() {
+        let mut limiter = RateLimiter::default();
+        let settings = RateLimitSettings {
+            enabled: false,
+            ..settings(1, 1)
+        };
+        let now = Instant::now();
+        for _ in 0..100 {
+            assert!(limiter.check("ci", &settings, now).is_ok());
+        }
+        assert!(limiter.take_suppressed(|_| false).is_empty());
+    }
+
+    #[test]
+    fn suppressed_counts_add_up_while_the_summary_is_open() {
+        let mut limiter = RateLimiter::default();
+        let settings = settings(1, 60);
+        let now = Instant::now();
+
+        assert!(limiter.check("ci", &settings, now).is_ok());
+        let _ = limiter.check("ci", &settings, now);
+        let _ = limiter.check("ci", &settings, now);
+        assert_eq!(
+            limiter.take_suppressed(|_| false),
+            vec![("ci".to_string(), 2)]
+        );
+        assert!(limiter.take_suppressed(|_| true).is_empty());
+
+        let _ = limiter.check("ci", &settings, now);
+        assert_eq!(
+            limiter.take_suppressed(|_| true),
+            vec![("ci".to_string(), 3)]
+        );
+    }
+
+    #[test]
+    fn suppressed_count_restarts_once_the_summary_was_dismissed() {
+        let mut limiter = RateLimiter::default();
+        let settings = settings(1, 60);
+        let now = Instant::now();
+
+        assert!(limiter.check("ci", &settings, now).is_ok());
+        let _ = limiter.check("ci", &settings, now);
+        let _ = limiter.check("ci", &settings, now);
+        assert_eq!(
+            limiter.take_suppressed(|_| false),
+            vec![("ci".to_string(), 2)]
+        );
+
+        let _ = limiter.check("ci", &settings, now);
+        assert_eq!(
+            limiter.take_suppressed(|_| false),
+            vec![("ci".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn idle_buckets_are_evicted_with_their_totals() {
+        let mut limiter = RateLimiter::default();
+        let settings = settings(1, 1);
+        let start = Instant::now();
+
+        assert!(limiter.check("ci", &settings, start).is_ok());
+        let _ = limiter.check("ci", &settings, start);
+        assert_eq!(
+            limiter.take_suppressed(|_| true),
+            vec![("ci".to_string(), 1)]
+        );
+
+        // Touching another source after the TTL drops the idle bucket
+        let later = start + BUCKET_IDLE_TTL;
+        assert!(limiter.check("deploy", &settings, later).is_ok());
+        assert!(!limiter.buckets.contains_key("ci"));
+
+        // A fresh bucket starts with a full burst and a zero total
+        assert!(limiter.check("ci", &settings, later).is_ok());
+        let _ = limiter.check("ci", &settings, later);
+        assert_eq!(
+            limiter.take_suppressed(|_| true),
+            vec![("ci".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn batches_cost_one_token_per_notification() {
+        let mut limiter = RateLimiter::default();
+        let settings = settings(5, 60);
+        let now = Instant::now();
+
+        assert!(limiter.check_batch(&[("ci", 3)], &settings, now).is_ok());
+        let (source, wait) = limiter
+            .check_batch(&[("ci", 3)], &settings, now)
+            .unwrap_err();
+        assert_eq!(source, "ci");
+        assert_eq!(wait, Duration::from_secs(1));
+        assert!(limiter.check_batch(&[("ci", 2)], &settings, now).is_ok());
+        assert!(limiter.check("ci", &settings, now).is_err());
+    }
+
+    #[test]
+    fn rejected_batches_take_no_tokens_and_count_every_notification() {
+        let mut limiter = RateLimiter::default();
+        let settings = settings(2, 60);
+        let now = Instant::now();
+
+        assert!(limiter
+            .check_batch(&[("deploy", 2)], &settings, now)
+            .is_ok());
+        let (source, _) = limiter
+            .check_batch(&[("ci", 2), ("deploy", 3)], &settings, now)
+            .unwrap_err();
+        assert_eq!(source, "deploy");
+
+        // "ci" was under its limit and keeps its tokens
+        assert!(limiter.check_batch(&[("ci", 2)], &settings, now).is_ok());
+        assert_eq!(
+            limiter.take_suppressed(|_| false),
+            vec![("ci".to_string(), 2), ("deploy".to_string(), 3)]
+        );
+    }
+
+    #[test]
+    fn batches_over_the_burst_need_a_full_bucket_and_leave_it_in_debt() {
+        let mut limiter = RateLimiter::default();
+        let settings = settings(5, 60);
+        let start = Instant::now();
+
+        assert!(limiter.check("ci", &settings, start).is_ok());
+        let (_, wait) = limiter
+            .check_batch(&[("ci", 500)], &settings, start)
+            .unwrap_err();
+        assert_eq!(wait, Duration::from_secs(1));
+
+        let full = start + Duration::from_secs(1);
+        assert!(limiter.check_batch(&[("ci", 500)], &settings, full).is_ok());
+        // 495 tokens in debt take 495 seconds to repay before the next one
+        let (_, wait) = limiter
+            .check_batch(&[("ci", 1)], &settings, full)
+            .unwrap_err();
+        assert_eq!(wait, Duration::from_secs(496));
+    }
+
+    #[test]
+    fn source_key_prefers_source_then_hashed_key_then_ip() {
+        let remote = Some("10.0.0.5:4000".parse().unwrap());
+        assert_eq!(source_key(Some("ci"), Some("secret"), remote), "ci");
+        let keyed = source_key(Some(""), Some("secret"), remote);
+        assert!(keyed.starts_with("key:"));
+        assert!(!keyed.contains("secret"));
+        assert_eq!(source_key(None, None, remote), "10.0.0.5");
+        assert_eq!(source_key(None, None, None), "unknown");
+    }
+}
@@ -0,0 +1,518 @@
+use crate::{NotifyPayload, Priority};
+use chrono::{Datelike, NaiveTime, Timelike};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+// ============================================================================
+// Rule File Format
+// ============================================================================
+
+/// Unknown keys are rejected: a misspelled condition would otherwise be
+/// left out and match everything, e.g. in a `drop` rule
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+struct RuleMatch {
+    /// Regex matched against the title
+    title: Option<String>,
+    project: Option<String>,
+    tmux_session: Option<String>,
+    source: Option<String>,
+    /// Local time window as "HH:MM"; `start` after `end` wraps past midnight
+    time: Option<TimeWindow>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+struct TimeWindow {
+    start: String,
+    end: String,
+    /// Weekdays as "mon".."sun" (empty = every day)
+    #[serde(default)]
+    days: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum RuleAction {
+    Drop,
+    SetPriority {
+        priority: Priority,
+    },
+    AddTag {
+        tag: String,
+    },
+    /// `pattern` defaults to the rule's title regex, or the whole title;
+    /// `replacement` may use `$1`-style capture references
+    RewriteTitle {
+        pattern: Option<String>,
+        replacement: String,
+    },
+    SetTtl {
+        secs: u64,
+    },
+    AutoDismiss,
+    Route {
+        queue: String,
+    },
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+struct Rule {
+    name: String,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+    #[serde(default, rename = "match")]
+    conditions: RuleMatch,
+    actions: Vec<RuleAction>,
+    /// Skip the remaining rules once this one matched
+    #[serde(default)]
+    stop: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+// ============================================================================
+// Compiled Rules
+// ============================================================================
+
+struct CompiledRule {
+    rule: Rule,
+    title: Option<Regex>,
+    time: Option<(NaiveTime, NaiveTime)>,
+    rewrites: Vec<Regex>,
+}
+
+#[derive(Default)]
+struct RuleSet {
+    rules: Vec<CompiledRule>,
+    modified: Option<SystemTime>,
+    error: Option<String>,
+}
+
+static WHOLE_TITLE: LazyLock<Regex> = LazyLock::new(|| Regex::new("(?s)^.*$").unwrap());
+
+static RULES: LazyLock<Mutex<RuleSet>> = LazyLock::new(|| Mutex::new(RuleSet::default()));
+
+fn get_rules_path() -> PathBuf {
+    crate::get_config_dir().join("rules.json")
+}
+
+pub fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value, "%H:%M")
+        .map_err(|e| format!("invalid time {:?}: {}", value, e))
+}
+
+fn compile(rule: Rule) -> Result<CompiledRule, String> {
+    let context = |e: regex::Error| format!("rule {:?}: {}", rule.name, e);
+
+    let title = match &rule.conditions.title {
+        Some(pattern) => Some(Regex::new(pattern).map_err(context)?),
+        None => None,
+    };
+    let time = match &rule.conditions.time {
+        Some(window) => Some((parse_time(&window.start)?, parse_time(&window.end)?)),
+        None => None,
+    };
+    let mut rewrites = Vec::new();
+    for action in &rule.actions {
+        if let RuleAction::RewriteTitle { pattern, .. } = action {
+            let regex = match (pattern, &title) {
+                (Some(pattern), _) => Regex::new(pattern).map_err(context)?,
+                (None, Some(title)) => title.clone(),
+                (None, None) => WHOLE_TITLE.clone(),
+            };
+            rewrites.push(regex);
+        }
+    }
+
+    Ok(CompiledRule {
+        rule,
+        title,
+        time,
+        rewrites,
+    })
+}
+
+fn load_rules(content: &str) -> Result<Vec<CompiledRule>, String> {
+    let rules: Vec<Rule> = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    rules.into_iter().map(compile).collect()
+}
+
+/// Reloads `rules.json` when its modification time changed. A broken file
+/// keeps the previous rules active and records the error for the UI.
+fn refresh(set: &mut RuleSet) {
+    let path = get_rules_path();
+    let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+    if modified == set.modified {
+        return;
+    }
+    set.modified = modified;
+
+    if modified.is_none() {
+        set.rules.clear();
+        set.error = None;
+        return;
+    }
+
+    match fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| load_rules(&content))
+    {
+        Ok(rules) => {
+            println!("[Lovnotifier] Loaded {} notification rules", rules.len());
+            set.rules = rules;
+            set.error = None;
+        }
+        Err(e) => {
+            println!("[Lovnotifier] Failed to load {:?}: {}", path, e);
+            set.error = Some(e);
+        }
+    }
+}
+
+// ============================================================================
+// Evaluation
+// ============================================================================
+
+#[derive(Debug, Serialize, Default)]
+pub struct RuleOutcome {
+    /// Names of the rules that matched, in evaluation order
+    pub matched: Vec<String>,
+    pub dropped: bool,
+    pub auto_dismiss: bool,
+}
+
+fn weekday_name(weekday: chrono::Weekday) -> &'static str {
+    match weekday {
+        chrono::Weekday::Mon => "mon",
+        chrono::Weekday::Tue => "tue",
+        chrono::Weekday::Wed => "wed",
+        chrono::Weekday::Thu => "thu",
+        chrono::Weekday::Fri => "fri",
+        chrono::Weekday::Sat => "sat",
+        chrono::Weekday::Sun => "sun",
+    }
+}
+
+/// Whether `now` falls in the window from `start` to `end` on one of `days`
+pub fn in_window(
+    now: &chrono::NaiveDateTime,
+    (start, end): (NaiveTime, NaiveTime),
+    days: &[String],
+) -> bool {
+    let time = NaiveTime::from_hms_opt(now.hour(), now.minute(), 0).unwrap_or_default();
+    let in_time = if start <= end {
+        time >= start && time < end
+    } else {
+        time >= start || time < end
+    };
+    if !in_time {
+        return false;
+    }
+    if days.is_empty() {
+        return true;
+    }
+    // A window wrapping past midnight belongs to the day it started on
+    let day = if start > end && time < end {
+        now.weekday().pred()
+    } else {
+        now.weekday()
+    };
+    days.iter()
+        .any(|d| d.eq_ignore_ascii_case(weekday_name(day)))
+}
+
+impl CompiledRule {
+    fn matches(&self, payload: &NotifyPayload, now: &chrono::NaiveDateTime) -> bool {
+        let conditions = &self.rule.conditions;
+        let field_matches = |expected: &Option<String>, actual: &Option<String>| match expected {
+            Some(expected) => actual.as_ref() == Some(expected),
+            None => true,
+        };
+
+        self.rule.enabled
+            && self
+                .title
+                .as_ref()
+                .is_none_or(|re| re.is_match(&payload.title))
+            && field_matches(&conditions.project, &payload.project)
+            && field_matches(&conditions.tmux_session, &payload.tmux_session)
+            && field_matches(&conditions.source, &payload.source)
+            && match (self.time, &conditions.time) {
+                (Some(window), Some(spec)) => in_window(now, window, &spec.days),
+                _ => true,
+            }
+    }
+
+    fn apply(&self, payload: &mut NotifyPayload, outcome: &mut RuleOutcome) {
+        let mut rewrites = self.rewrites.iter();
+        for action in &self.rule.actions {
+            match action {
+                RuleAction::Drop => outcome.dropped = true,
+                RuleAction::SetPriority { priority } => payload.priority = *priority,
+                RuleAction::AddTag { tag } => {
+                    if !payload.tags.contains(tag) {
+                        payload.tags.push(tag.clone());
+                    }
+                }
+                RuleAction::RewriteTitle { replacement, .. } => {
+                    if let Some(regex) = rewrites.next() {
+                        payload.title = regex
+                            .replace_all(&payload.title, replacement.as_str())
+                            .into_owned();
+                    }
+                }
+                RuleAction::SetTtl { secs } => payload.ttl_secs = Some(*secs),
+                RuleAction::AutoDismiss => outcome.auto_dismiss = true,
+                RuleAction::Route { queue } => payload.queue = Some(queue.clone()),
+            }
+        }
+    }
+}
+
+fn evaluate_with(
+    rules: &[CompiledRule],
+    payload: &mut NotifyPayload,
+    now: &chrono::NaiveDateTime,
+) -> RuleOutcome {
+    let mut outcome = RuleOutcome::default();
+    for rule in rules {
+        if !rule.matches(payload, now) {
+            continue;
+        }
+        outcome.matched.push(rule.rule.name.clone());
+        rule.apply(payload, &mut outcome);
+        if outcome.dropped || rule.rule.stop {
+            break;
+        }
+    }
+    outcome
+}
+
+/// Applies the rules in `rules.json` to an incoming notification in place
+pub fn evaluate(payload: &mut NotifyPayload) -> RuleOutcome {
+    let mut set = RULES.lock().unwrap();
+    refresh(&mut set);
+    evaluate_with(&set.rules, payload, &chrono::Local::now().naive_local())
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuleTestResult {
+    pub outcome: RuleOutcome,
+    /// The notification as it would be queued after the rules ran
+    pub payload: NotifyPayload,
+}
+
+/// Dry-runs `payload` against the rules. `rules` overrides the file contents
+/// so the settings UI can try edits before saving them.
+pub fn test(mut payload: NotifyPayload, rules: Option<String>) -> Result<RuleTestResult, String> {
+    let now = chrono::Local::now().naive_local();
+    let outcome = match rules {
+        Some(content) => evaluate_with(&load_rules(&content)?, &mut payload, &now),
+        None => {
+            let mut set = RULES.lock().unwrap();
+            refresh(&mut set);
+            if let Some(error) = &set.error {
+                return Err(error.clone());
+            }
+            evaluate_with(&set.rules, &mut payload, &now)
+        }
+    };
+    Ok(RuleTestResult { outcome, payload })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, NaiveDateTime};
+
+    fn payload(title: &str) -> NotifyPayload {
+        serde_json::from_value(serde_json::json!({
+            "title": title,
+            "project": "api",
+            "source": "ci",
+        }))
+        .unwrap()
+    }
+
+    fn rules(json: serde_json::Value) -> Vec<CompiledRule> {
+        load_rules(&json.to_string()).unwrap()
+    }
+
+    // 2026-10-16 is a Friday
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn window(start: &str, end: &str) -> (NaiveTime, NaiveTime) {
+        (parse_time(start).unwrap(), parse_time(end).unwrap())
+    }
+
+    #[test]
+    fn misspelled_keys_reject_the_rules() {
+        let misspelled = serde_json::json!([{
+            "name": "Drop api",
+            "match": {"projcet": "api"},
+            "actions": [{"action": "drop"}],
+        }]);
+        let error = load_rules(&misspelled.to_string()).err().unwrap();
+        assert!(error.contains("unknown field `projcet`"), "{}", error);
+
+        let misspelled_window = serde_json::json!([{
+            "name": "Nights",
+            "match": {"time": {"start": "22:00", "end": "07:00", "day": ["mon"]}},
+            "actions": [{"action": "drop"}],
+        }]);
+        assert!(load_rules(&misspelled_window.to_string()).is_err());
+
+        let misspelled_rule = serde_json::json!([{
+            "name": "Drop api",
+            "macth": {"project": "api"},
+            "actions": [{"action": "drop"}],
+        }]);
+        assert!(load_rules(&misspelled_rule.to_string()).is_err());
+    }
+
+    #[test]
+    fn window_within_one_day() {
+        let office = window("09:00", "17:00");
+        assert!(!in_window(&at(16, 8, 59), office, &[]));
+        assert!(in_window(&at(16, 9, 0), office, &[]));
+        assert!(in_window(&at(16, 16, 59), office, &[]));
+        assert!(!in_window(&at(16, 17, 0), office, &[]));
+    }
+
+    #[test]
+    fn window_wrapping_past_midnight() {
+        let night = window("22:00", "07:00");
+        assert!(in_window(&at(16, 23, 30), night, &[]));
+        assert!(in_window(&at(17, 6, 59), night, &[]));
+        assert!(!in_window(&at(17, 7, 0), night, &[]));
+        assert!(!in_window(&at(16, 21, 59), night, &[]));
+    }
+
+    #[test]
+    fn wrapped_window_belongs_to_the_day_it_started() {
+        let night = window("22:00", "07:00");
+        let fridays = ["Fri".to_string()];
+        assert!(in_window(&at(16, 23, 0), night, &fridays));
+        // Early Saturday is still Friday night
+        assert!(in_window(&at(17, 3, 0), night, &fridays));
+        assert!(!in_window(&at(17, 23, 0), night, &fridays));
+        assert!(!in_window(&at(16, 3, 0), night, &fridays));
+    }
+
+    #[test]
+    fn actions_of_every_matching_rule_accumulate() {
+        let rules = rules(serde_json::json!([
+            {"name": "tag ci", "match": {"source": "ci"}, "actions": [
+                {"action": "add_tag", "tag": "ci"},
+            ]},
+            {"name": "other project", "match": {"project": "web"}, "actions": [
+                {"action": "drop"},
+            ]},
+            {"name": "failures", "match": {"title": "failed"}, "actions": [
+                {"action": "set_priority", "priority": "urgent"},
+                {"action": "route", "queue": "alerts"},
+            ]},
+        ]));
+        let mut payload = payload("Build failed");
+        let outcome = evaluate_with(&rules, &mut payload, &at(16, 12, 0));
+        assert_eq!(outcome.matched, ["tag ci", "failures"]);
+        assert!(!outcome.dropped);
+        assert_eq!(payload.tags, ["ci"]);
+        assert_eq!(payload.priority, Priority::Urgent);
+        assert_eq!(payload.queue.as_deref(), Some("alerts"));
+    }
+
+    #[test]
+    fn stop_ends_evaluation_at_the_first_match() {
+        let rules = rules(serde_json::json!([
+            {"name": "first", "stop": true, "actions": [{"action": "set_ttl", "secs": 60}]},
+            {"name": "second", "actions": [{"action": "auto_dismiss"}]},
+        ]));
+        let mut payload = payload("Build passed");
+        let outcome = evaluate_with(&rules, &mut payload, &at(16, 12, 0));
+        assert_eq!(outcome.matched, ["first"]);
+        assert_eq!(payload.ttl_secs, Some(60));
+        assert!(!outcome.auto_dismiss);
+    }
+
+    #[test]
+    fn drop_stops_evaluation() {
+        let rules = rules(serde_json::json!([
+            {"name": "quiet", "actions": [{"action": "drop"}]},
+            {"name": "tag", "actions": [{"action": "add_tag", "tag": "late"}]},
+        ]));
+        let mut payload = payload("Build passed");
+        let outcome = evaluate_with(&rules, &mut payload, &at(16, 12, 0));
+        assert!(outcome.dropped);
+        assert_eq!(outcome.matched, ["quiet"]);
+        assert!(payload.tags.is_empty());
+    }
+
+    #[test]
+    fn disabled_and_out_of_window_rules_are_skipped() {
+        let rules = rules(serde_json::json!([
+            {"name": "off", "enabled": false, "actions": [{"action": "drop"}]},
+            {"name": "nights", "match": {"time": {"start": "22:00", "end": "07:00"}},
+             "actions": [{"action": "drop"}]},
+        ]));
+        let mut payload = payload("Build passed");
+        let outcome = evaluate_with(&rules, &mut payload, &at(16, 12, 0));
+        assert!(outcome.matched.is_empty());
+        let outcome = evaluate_with(&rules, &mut payload, &at(16, 23, 0));
+        assert_eq!(outcome.matched, ["nights"]);
+    }
+
+    #[test]
+    fn rewrite_title_uses_the_match_regex_and_its_captures() {
+        let rules = rules(serde_json::json!([
+            {"name": "shorten", "match": {"title": "^Build (\\w+) on (.+)$"}, "actions": [
+                {"action": "rewrite_title", "replacement": "$2: $1"},
+            ]},
+        ]));
+        let mut payload = payload("Build failed on main");
+        evaluate_with(&rules, &mut payload, &at(16, 12, 0));
+        assert_eq!(payload.title, "main: failed");
+    }
+
+    #[test]
+    fn rewrite_title_with_its_own_pattern_or_the_whole_title() {
+        let rules = rules(serde_json::json!([
+            {"name": "ticket", "actions": [
+                {"action": "rewrite_title", "pattern": "#(\\d+)", "replacement": "PR-$1"},
+            ]},
+            {"name": "prefix", "actions": [
+                {"action": "rewrite_title", "replacement": "[ci] $0"},
+            ]},
+        ]));
+        let mut payload = payload("Review #12 and #13");
+        evaluate_with(&rules, &mut payload, &at(16, 12, 0));
+        assert_eq!(payload.title, "[ci] Review PR-12 and PR-13");
+    }
+
+    #[test]
+    fn invalid_rules_report_the_rule_name() {
+        let error = load_rules(r#"[{"name": "broken", "match": {"title": "("}, "actions": []}]"#)
+            .err()
+            .unwrap();
+        assert!(error.contains("broken"));
+        assert!(load_rules(
+            r#"[{"name": "late", "match": {"time": {"start": "25:00", "end": "07:00"}},
+                 "actions": []}]"#
+        )
+        .is_err());
+    }
+}
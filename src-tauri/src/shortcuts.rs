@@ -0,0 +1,294 @@
+use crate::{KeyBinding, NotifierSettings, Outcome, ReviewItem, ShortcutAction};
+use std::cmp::Reverse;
+use std::sync::{LazyLock, Mutex};
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+/// Shortcuts currently registered with the OS
+static ACTIVE_BINDINGS: LazyLock<Mutex<Vec<(Shortcut, ShortcutAction)>>> =
+    LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Item the last `CyclePending` press went to
+static CYCLE_CURSOR: LazyLock<Mutex<Option<String>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Parses an accelerator such as `F4`, `Alt+Space` or `CmdOrCtrl+Shift+N`
+pub fn parse(accelerator: &str) -> Result<Shortcut, String> {
+    accelerator
+        .trim()
+        .parse()
+        .map_err(|e| format!("Invalid shortcut \"{}\": {}", accelerator, e))
+}
+
+/// `shortcut` consumes the oldest item; `keymap` adds further bindings
+pub fn bindings(settings: &NotifierSettings) -> Vec<KeyBinding> {
+    let primary = (!settings.shortcut.trim().is_empty()).then(|| KeyBinding {
+        accelerator: settings.shortcut.clone(),
+        action: ShortcutAction::ConsumeOldest,
+    });
+    primary.into_iter().chain(settings.keymap.clone()).collect()
+}
+
+fn parse_bindings(bindings: &[KeyBinding]) -> Result<Vec<(Shortcut, ShortcutAction)>, String> {
+    let mut parsed: Vec<(Shortcut, ShortcutAction)> = Vec::new();
+    for binding in bindings {
+        let shortcut = parse(&binding.accelerator)?;
+        if parsed.iter().any(|(existing, _)| *existing == shortcut) {
+            return Err(format!(
+                "Shortcut \"{}\" is bound twice",
+                binding.accelerator
+            ));
+        }
+        parsed.push((shortcut, binding.action));
+    }
+    Ok(parsed)
+}
+
+/// Checks that every binding in `settings` parses and none is bound twice
+pub fn validate(settings: &NotifierSettings) -> Result<(), String> {
+    parse_bindings(&bindings(settings)).map(|_| ())
+}
+
+/// Registers the bindings from `settings` in place of the current ones. If
+/// any of them cannot be registered, e.g. because another app holds it, the
+/// previous bindings are restored.
+///
+/// `ACTIVE_BINDINGS` is not held while registering: the plugin registers on
+/// the main thread and waits for it, where the shortcut handler may be
+/// waiting for the lock.
+pub fn apply<R: Runtime>(app: &AppHandle<R>, settings: &NotifierSettings) -> Result<(), String> {
+    let bindings = bindings(settings);
+    let parsed = parse_bindings(&bindings)?;
+    let global_shortcut = app.global_shortcut();
+    let previous = ACTIVE_BINDINGS.lock().unwrap().clone();
+    if previous == parsed {
+        return Ok(());
+    }
+
+    for (shortcut, _) in &previous {
+        let _ = global_shortcut.unregister(*shortcut);
+    }
+    for (index, (shortcut, _)) in parsed.iter().enumerate() {
+        if let Err(e) = global_shortcut.register(*shortcut) {
+            for (registered, _) in &parsed[..index] {
+                let _ = global_shortcut.unregister(*registered);
+            }
+            let restored: Vec<(Shortcut, ShortcutAction)> = previous
+                .into_iter()
+                .filter(|(shortcut, _)| global_shortcut.register(*shortcut).is_ok())
+                .collect();
+            *ACTIVE_BINDINGS.lock().unwrap() = restored;
+            return Err(format!(
+                "Cannot register shortcut \"{}\": {}",
+                bindings[index].accelerator, e
+            ));
+        }
+    }
+
+    for binding in &bindings {
+        println!(
+            "[Lovnotifier] Registered global shortcut {} ({:?})",
+            binding.accelerator, binding.action
+        );
+    }
+    *ACTIVE_BINDINGS.lock().unwrap() = parsed;
+    Ok(())
+}
+
+/// Picks the item an action applies to. Stale items come last where the
+/// action navigates; they cannot be navigated to. Items are ordered by `seq`,
+/// as timestamps only have whole seconds and a batch shares one.
+fn pick(queue: &[ReviewItem], action: ShortcutAction) -> Option<&ReviewItem> {
+    match action {
+        ShortcutAction::ConsumeOldest => queue
+            .iter()
+            .min_by_key(|item| (item.stale_since.is_some(), item.seq)),
+        ShortcutAction::ConsumeNewest => queue
+            .iter()
+            .min_by_key(|item| (item.stale_since.is_some(), Reverse(item.seq))),
+        ShortcutAction::ConsumeHighestPriority => queue
+            .iter()
+            .min_by_key(|item| (item.stale_since.is_some(), Reverse(item.priority), item.seq)),
+        ShortcutAction::DismissOldest => queue.iter().min_by_key(|item| item.seq),
+        _ => None,
+    }
+}
+
+/// Navigates to the pending item after the one the previous press went to,
+/// wrapping around. The item stays in the queue.
+fn cycle_pending<R: Runtime>(app: &AppHandle<R>) {
+    let next = {
+        let queue = crate::focus::visible(&crate::REVIEW_QUEUE.lock().unwrap());
+        let mut items: Vec<&ReviewItem> = queue
+            .iter()
+            .filter(|item| item.stale_since.is_none())
+            .collect();
+        items.sort_by_key(|item| item.seq);
+        let mut cursor = CYCLE_CURSOR.lock().unwrap();
+        let position = cursor
+            .as_ref()
+            .and_then(|id| items.iter().position(|item| item.id == *id));
+        let next = match position {
+            Some(index) => items.get((index + 1) % items.len()),
+            None => items.first(),
+        }
+        .map(|item| (*item).clone());
+        *cursor = next.as_ref().map(|item| item.id.clone());
+        next
+    };
+
+    let Some(item) = next else {
+        return;
+    };
+    let _ = app.emit("review-item-selected", &item.id);
+    if let Some(target) = item.mux_target() {
+        tauri::async_runtime::spawn_blocking(move || crate::navigate_to_target(&target));
+    }
+}
+
+fn open_quick_picker<R: Runtime>(app: &AppHandle<R>) {
+    crate::show_float_window(app);
+    if let Some(window) = app.get_webview_window("float") {
+        let _ = window.set_focus();
+    }
+    let _ = app.emit("open-quick-picker", ());
+}
+
+fn dispatch<R: Runtime>(app: &AppHandle<R>, action: ShortcutAction) {
+    match action {
+        ShortcutAction::ToggleFloatWindow => crate::toggle_float_window(app),
+        ShortcutAction::QuickPicker => open_quick_picker(app),
+        ShortcutAction::CyclePending => cycle_pending(app),
+        ShortcutAction::OpenSettings => crate::show_settings_window(app),
+        ShortcutAction::ToggleDnd => crate::dnd::toggle(app),
+        _ => {
+            let id = {
+                let queue = crate::focus::visible(&crate::REVIEW_QUEUE.lock().unwrap());
+                pick(&queue, action).map(|item| item.id.clone())
+            };
+            let Some(id) = id else {
+                return;
+            };
+            if action == ShortcutAction::DismissOldest {
+                crate::complete_review_items(app, &[id], Outcome::Dismissed);
+            } else {
+                crate::consume_review_item(app, &id);
+            }
+        }
+    }
+}
+
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, shortcut, event| {
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+            let action = ACTIVE_BINDINGS
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|(active, _)| active == shortcut)
+                .map(|(_, action)| *action);
+            if let Some(action) = action {
+                dispatch(app, action);
+            }
+        })
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(seq: u64, priority: &str) -> ReviewItem {
+        serde_json::from_value(serde_json::json!({
+            "id": seq.to_string(),
+            "seq": seq,
+            "title": "Build finished",
+            // A batch arrives within one second
+            "timestamp": 1_700_000_000,
+            "priority": priority,
+        }))
+        .unwrap()
+    }
+
+    fn stale(mut item: ReviewItem) -> ReviewItem {
+        item.stale_since = Some(1_700_000_100);
+        item
+    }
+
+    fn picked(queue: &[ReviewItem], action: ShortcutAction) -> Option<u64> {
+        pick(queue, action).map(|item| item.seq)
+    }
+
+    #[test]
+    fn consume_orders_items_of_the_same_second_by_seq() {
+        let queue = [item(11, "normal"), item(10, "normal"), item(12, "normal")];
+        assert_eq!(picked(&queue, ShortcutAction::ConsumeOldest), Some(10));
+        assert_eq!(picked(&queue, ShortcutAction::ConsumeNewest), Some(12));
+        assert_eq!(picked(&queue, ShortcutAction::DismissOldest), Some(10));
+    }
+
+    #[test]
+    fn highest_priority_takes_the_oldest_among_equals() {
+        let queue = [
+            item(10, "normal"),
+            item(12, "urgent"),
+            item(11, "urgent"),
+            item(13, "high"),
+        ];
+        assert_eq!(
+            picked(&queue, ShortcutAction::ConsumeHighestPriority),
+            Some(11)
+        );
+    }
+
+    #[test]
+    fn navigating_actions_put_stale_items_last() {
+        let queue = [
+            stale(item(10, "urgent")),
+            item(11, "low"),
+            stale(item(12, "low")),
+        ];
+        assert_eq!(picked(&queue, ShortcutAction::ConsumeOldest), Some(11));
+        assert_eq!(picked(&queue, ShortcutAction::ConsumeNewest), Some(11));
+        assert_eq!(
+            picked(&queue, ShortcutAction::ConsumeHighestPriority),
+            Some(11)
+        );
+        // Dismissing does not navigate, so stale items are fair game
+        assert_eq!(picked(&queue, ShortcutAction::DismissOldest), Some(10));
+
+        let all_stale = [stale(item(10, "low")), stale(item(11, "low"))];
+        assert_eq!(picked(&all_stale, ShortcutAction::ConsumeNewest), Some(11));
+    }
+
+    #[test]
+    fn other_actions_pick_nothing() {
+        let queue = [item(10, "normal")];
+        assert_eq!(picked(&queue, ShortcutAction::ToggleFloatWindow), None);
+        assert_eq!(picked(&queue, ShortcutAction::CyclePending), None);
+        assert_eq!(picked(&[], ShortcutAction::ConsumeOldest), None);
+    }
+
+    #[test]
+    fn validate_rejects_bad_and_duplicate_accelerators() {
+        let mut settings = NotifierSettings {
+            shortcut: "F4".to_string(),
+            ..Default::default()
+        };
+        assert!(validate(&settings).is_ok());
+
+        settings.keymap = vec![KeyBinding {
+            accelerator: "F4".to_string(),
+            action: ShortcutAction::CyclePending,
+        }];
+        assert!(validate(&settings).unwrap_err().contains("bound twice"));
+
+        settings.keymap[0].accelerator = "Shift+Nope".to_string();
+        assert!(validate(&settings)
+            .unwrap_err()
+            .contains("Invalid shortcut"));
+    }
+}
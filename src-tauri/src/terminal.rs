@@ -0,0 +1,1641 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+// ============================================================================
+// Command Runner
+// ============================================================================
+
+#[derive(Debug, Clone, Default)]
+pub struct CommandOutput {
+    pub success: bool,
+    /// Exit code; `None` when the program could not be started, was killed
+    /// by a signal or timed out
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl CommandOutput {
+    fn failure(stderr: String) -> Self {
+        Self {
+            stderr,
+            ..Self::default()
+        }
+    }
+}
+
+/// Runs external programs. Backends only talk to the system through this,
+/// so they can be exercised with a fake runner that records invocations.
+pub trait CommandRunner {
+    fn run(&self, program: &str, args: &[&str]) -> CommandOutput;
+
+    /// Like `run`, but gives up and kills the program after `timeout`
+    fn run_with_timeout(&self, program: &str, args: &[&str], _timeout: Duration) -> CommandOutput {
+        self.run(program, args)
+    }
+}
+
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn run(&self, program: &str, args: &[&str]) -> CommandOutput {
+        match std::process::Command::new(program).args(args).output() {
+            Ok(output) => CommandOutput {
+                success: output.status.success(),
+                code: output.status.code(),
+                stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            },
+            Err(e) => CommandOutput::failure(format!("failed to run {}: {}", program, e)),
+        }
+    }
+
+    fn run_with_timeout(&self, program: &str, args: &[&str], timeout: Duration) -> CommandOutput {
+        use std::process::Stdio;
+
+        let mut child = match std::process::Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => return CommandOutput::failure(format!("failed to run {}: {}", program, e)),
+        };
+
+        // Drain the pipes on their own threads so a chatty program cannot
+        // block on a full pipe while we wait for it
+        let stdout = read_in_background(child.stdout.take());
+        let stderr = read_in_background(child.stderr.take());
+
+        let deadline = Instant::now() + timeout;
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break Some(status),
+                Ok(None) if Instant::now() < deadline => {
+                    std::thread::sleep(Duration::from_millis(10))
+                }
+                _ => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    break None;
+                }
+            }
+        };
+
+        let stdout = stdout.join().unwrap_or_default();
+        let mut stderr = stderr.join().unwrap_or_default();
+        if status.is_none() {
+            stderr.push_str(&format!("{} timed out after {:?}", program, timeout));
+        }
+        CommandOutput {
+            success: status.is_some_and(|s| s.success()),
+            code: status.and_then(|s| s.code()),
+            stdout,
+            stderr,
+        }
+    }
+}
+
+fn read_in_background<R: Read + Send + 'static>(
+    pipe: Option<R>,
+) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        String::from_utf8_lossy(&buffer).into_owned()
+    })
+}
+
+// ============================================================================
+// Multiplexers
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum MuxKind {
+    #[default]
+    Tmux,
+    Zellij,
+    Screen,
+}
+
+impl MuxKind {
+    fn program(self) -> &'static str {
+        match self {
+            MuxKind::Tmux => "tmux",
+            MuxKind::Zellij => "zellij",
+            MuxKind::Screen => "screen",
+        }
+    }
+}
+
+/// Where a notification came from inside a terminal multiplexer. `window`
+/// is the tmux/screen window or the Zellij tab. For tmux, a pane id (`%42`)
+/// survives window renumbering and pane splits, so it takes precedence over
+/// the session/window/pane coordinates when both are known.
+#[derive(Debug, Clone, Default)]
+pub struct MuxTarget {
+    pub kind: MuxKind,
+    /// tmux server socket; a path is passed as `-S`, a bare name as `-L`
+    pub socket: Option<String>,
+    pub pane_id: Option<String>,
+    pub session: String,
+    pub window: String,
+    pub pane: String,
+    /// ssh destination when the multiplexer runs on another machine
+    pub ssh_target: Option<String>,
+    /// Not used by the multiplexer; available to navigation profiles
+    pub project_path: Option<String>,
+    pub session_id: Option<String>,
+}
+
+impl MuxTarget {
+    /// What title-matching backends look for in the local terminal: the
+    /// tmux session, or for remote targets the host the SSH tab is logged into
+    pub fn title_hint(&self) -> &str {
+        match &self.ssh_target {
+            Some(target) => target.rsplit('@').next().unwrap_or(target),
+            None => &self.session,
+        }
+    }
+}
+
+// ============================================================================
+// tmux
+// ============================================================================
+
+/// How long a query made while admitting a notification or polling for the
+/// tmux watcher may wait; a hung server, e.g. behind a stale socket, must
+/// hold up neither
+pub const TMUX_QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+fn tmux_args<'a>(socket: Option<&'a str>, args: &[&'a str]) -> Vec<&'a str> {
+    let mut full = Vec::with_capacity(args.len() + 2);
+    if let Some(socket) = socket.filter(|s| !s.is_empty()) {
+        full.push(if socket.contains('/') { "-S" } else { "-L" });
+        full.push(socket);
+    }
+    full.extend_from_slice(args);
+    full
+}
+
+/// Runs tmux against `socket`, or the default server when unset
+pub fn run_tmux(runner: &dyn CommandRunner, socket: Option<&str>, args: &[&str]) -> CommandOutput {
+    runner.run("tmux", &tmux_args(socket, args))
+}
+
+/// Like `run_tmux`, but kills tmux after `timeout`
+pub fn run_tmux_with_timeout(
+    runner: &dyn CommandRunner,
+    socket: Option<&str>,
+    args: &[&str],
+    timeout: Duration,
+) -> CommandOutput {
+    runner.run_with_timeout("tmux", &tmux_args(socket, args), timeout)
+}
+
+/// Whether tmux failed because no server is listening on the socket
+pub fn is_server_unreachable(stderr: &str) -> bool {
+    stderr.contains("no server running") || stderr.contains("error connecting to")
+}
+
+/// Looks up the session, window index and pane index currently holding `pane_id`
+fn resolve_pane_id(
+    runner: &dyn CommandRunner,
+    socket: Option<&str>,
+    pane_id: &str,
+) -> Result<(String, String, String), String> {
+    let output = run_tmux(
+        runner,
+        socket,
+        &[
+            "display-message",
+            "-p",
+            "-t",
+            pane_id,
+            "#{session_name}\t#{window_index}\t#{pane_index}",
+        ],
+    );
+    if !output.success {
+        return Err(output.stderr);
+    }
+    let line = output.stdout.trim_end_matches('\n');
+    let mut fields = line.split('\t');
+    match (fields.next(), fields.next(), fields.next()) {
+        (Some(session), Some(window), Some(pane)) if !session.is_empty() => {
+            Ok((session.to_string(), window.to_string(), pane.to_string()))
+        }
+        _ => Err(format!(
+            "unexpected tmux output for {}: {:?}",
+            pane_id, line
+        )),
+    }
+}
+
+/// CSI sequences, OSC strings and two-byte escapes
+static ANSI_ESCAPE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\x1b(?:\[[0-?]*[ -/]*[@-~]|\][^\x07\x1b]*(?:\x07|\x1b\\)|[@-Z\\-_])").unwrap()
+});
+
+pub fn strip_ansi(text: &str) -> String {
+    ANSI_ESCAPE.replace_all(text, "").into_owned()
+}
+
+/// Returns the last `lines` non-blank-trailing lines of the target tmux pane
+/// as plain text, or `None` when the pane cannot be captured
+pub fn capture_pane(
+    runner: &dyn CommandRunner,
+    target: &MuxTarget,
+    lines: usize,
+) -> Option<String> {
+    if target.kind != MuxKind::Tmux {
+        return None;
+    }
+    let pane_target = match &target.pane_id {
+        Some(pane_id) => pane_id.clone(),
+        None if target.session.is_empty() => return None,
+        None => {
+            let mut spec = target.session.clone();
+            if !target.window.is_empty() {
+                spec = format!("{}:{}", spec, target.window);
+                if !target.pane.is_empty() {
+                    spec = format!("{}.{}", spec, target.pane);
+                }
+            }
+            spec
+        }
+    };
+
+    // -S reaches into the scrollback in case the visible area is mostly blank
+    let start = format!("-{}", lines);
+    let output = run_tmux_with_timeout(
+        runner,
+        target.socket.as_deref(),
+        &["capture-pane", "-p", "-J", "-t", &pane_target, "-S", &start],
+        TMUX_QUERY_TIMEOUT,
+    );
+    if !output.success {
+        return None;
+    }
+
+    let text = strip_ansi(&output.stdout);
+    let all: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let end = all.iter().rposition(|line| !line.is_empty())? + 1;
+    Some(all[end.saturating_sub(lines)..end].join("\n"))
+}
+
+// ============================================================================
+// Remote Hosts
+// ============================================================================
+
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+fn is_mux_program(program: &str) -> bool {
+    [MuxKind::Tmux, MuxKind::Zellij, MuxKind::Screen]
+        .iter()
+        .any(|kind| kind.program() == program)
+}
+
+/// How long a remote multiplexer command may take when the caller set no
+/// limit. `ConnectTimeout` only covers the connection, not a hung session.
+const REMOTE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Runs the multiplexer on another machine over ssh. Everything else still
+/// runs locally through `inner`, so backends keep focusing the local terminal.
+pub struct SshRunner<'a> {
+    pub inner: &'a dyn CommandRunner,
+    /// Program and options; the destination and remote command are appended
+    pub command: &'a [String],
+    pub target: &'a str,
+}
+
+impl SshRunner<'_> {
+    fn run_remote(&self, program: &str, args: &[&str], timeout: Duration) -> CommandOutput {
+        let Some((ssh, options)) = self.command.split_first() else {
+            return CommandOutput::failure("terminal.ssh_command is empty".to_string());
+        };
+
+        // ssh hands the command to the remote shell as one string
+        let remote = std::iter::once(program)
+            .chain(args.iter().copied())
+            .map(shell_quote)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut full: Vec<&str> = options.iter().map(String::as_str).collect();
+        full.push(self.target);
+        full.push(&remote);
+        self.inner.run_with_timeout(ssh, &full, timeout)
+    }
+}
+
+impl CommandRunner for SshRunner<'_> {
+    fn run(&self, program: &str, args: &[&str]) -> CommandOutput {
+        if is_mux_program(program) {
+            self.run_remote(program, args, REMOTE_TIMEOUT)
+        } else {
+            self.inner.run(program, args)
+        }
+    }
+
+    fn run_with_timeout(&self, program: &str, args: &[&str], timeout: Duration) -> CommandOutput {
+        if is_mux_program(program) {
+            self.run_remote(program, args, timeout)
+        } else {
+            self.inner.run_with_timeout(program, args, timeout)
+        }
+    }
+}
+
+// ============================================================================
+// Settings
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TerminalBackendKind {
+    /// iTerm2 on macOS, plain tmux elsewhere
+    #[default]
+    Auto,
+    Iterm2,
+    Tmux,
+    Kitty,
+    Wezterm,
+    X11,
+}
+
+/// One step of a navigation profile. `program` and `args` may contain
+/// placeholders like `{session}`; they are passed as argv, not through a shell.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct CommandTemplate {
+    pub program: String,
+    pub args: Vec<String>,
+    pub timeout_ms: u64,
+    /// Exit code that counts as success
+    pub exit_code: i32,
+    /// Text stdout must contain for the step to succeed
+    pub stdout_contains: Option<String>,
+    /// Carry on with the next step when this one fails
+    pub optional: bool,
+}
+
+impl Default for CommandTemplate {
+    fn default() -> Self {
+        Self {
+            program: String::new(),
+            args: Vec::new(),
+            timeout_ms: 5000,
+            exit_code: 0,
+            stdout_contains: None,
+            optional: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct NavigationProfile {
+    pub name: String,
+    pub commands: Vec<CommandTemplate>,
+    /// Select the tmux window and pane after the commands ran; turn off when
+    /// the commands take care of it themselves
+    pub select_pane: bool,
+}
+
+impl Default for NavigationProfile {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            commands: Vec::new(),
+            select_pane: true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct TerminalSettings {
+    pub backend: TerminalBackendKind,
+    /// kitty remote control address, e.g. `unix:/tmp/kitty` (`listen_on` in kitty.conf)
+    pub kitty_socket: Option<String>,
+    /// How to reach remote hosts; the ssh destination and tmux command are appended
+    pub ssh_command: Vec<String>,
+    /// Name of the entry in `profiles` that replaces `backend`
+    pub profile: Option<String>,
+    pub profiles: Vec<NavigationProfile>,
+}
+
+impl Default for TerminalSettings {
+    fn default() -> Self {
+        Self {
+            backend: TerminalBackendKind::default(),
+            kitty_socket: None,
+            ssh_command: ["ssh", "-o", "BatchMode=yes", "-o", "ConnectTimeout=5"]
+                .map(String::from)
+                .to_vec(),
+            profile: None,
+            profiles: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SnapshotSettings {
+    /// Capture the sender's tmux pane when a notification arrives
+    pub enabled: bool,
+    pub lines: usize,
+}
+
+impl Default for SnapshotSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            lines: 20,
+        }
+    }
+}
+
+// ============================================================================
+// Backends
+// ============================================================================
+
+/// Brings the terminal that shows a tmux session to the front. Selecting the
+/// window and pane inside tmux happens afterwards and is shared by all backends.
+/// `target.session` is always resolved by the time a backend sees it.
+pub trait TerminalBackend {
+    fn name(&self) -> &'static str;
+    fn activate(&self, runner: &dyn CommandRunner, target: &MuxTarget) -> CommandOutput;
+
+    /// Whether `navigate` should select the window and pane afterwards
+    fn selects_pane(&self) -> bool {
+        true
+    }
+
+    /// Backends that drive the multiplexer itself only work with their own
+    fn handles(&self, _kind: MuxKind) -> bool {
+        true
+    }
+}
+
+pub struct Iterm2Backend;
+
+impl TerminalBackend for Iterm2Backend {
+    fn name(&self) -> &'static str {
+        "iterm2"
+    }
+
+    fn activate(&self, runner: &dyn CommandRunner, target: &MuxTarget) -> CommandOutput {
+        let script = format!(
+            r#"
+            tell application "iTerm2"
+                activate
+                repeat with w in windows
+                    repeat with t in tabs of w
+                        repeat with s in sessions of t
+                            if name of s contains "{}" then
+                                select w
+                                select t
+                                select s
+                                return "FOUND"
+                            end if
+                        end repeat
+                    end repeat
+                end repeat
+                return "NOT_FOUND"
+            end tell
+        "#,
+            target
+                .title_hint()
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+        );
+        let mut output = runner.run("osascript", &["-e", &script]);
+        output.success = output.success && output.stdout.trim() == "FOUND";
+        output
+    }
+}
+
+/// Switches the most recently used tmux client to the session; no window
+/// manager involvement, so it works on any platform and over plain SSH
+pub struct TmuxBackend;
+
+impl TerminalBackend for TmuxBackend {
+    fn name(&self) -> &'static str {
+        "tmux"
+    }
+
+    fn activate(&self, runner: &dyn CommandRunner, target: &MuxTarget) -> CommandOutput {
+        run_tmux(
+            runner,
+            target.socket.as_deref(),
+            &["switch-client", "-t", &target.session],
+        )
+    }
+
+    fn handles(&self, kind: MuxKind) -> bool {
+        kind == MuxKind::Tmux
+    }
+}
+
+/// Focuses the kitty window whose title mentions the session (tmux sets it
+/// via `set-titles`). Needs `allow_remote_control` in kitty.conf.
+pub struct KittyBackend {
+    pub socket: Option<String>,
+}
+
+impl TerminalBackend for KittyBackend {
+    fn name(&self) -> &'static str {
+        "kitty"
+    }
+
+    fn activate(&self, runner: &dyn CommandRunner, target: &MuxTarget) -> CommandOutput {
+        let matcher = format!("title:{}", regex::escape(target.title_hint()));
+        let mut args = vec!["@"];
+        if let Some(socket) = &self.socket {
+            args.extend(["--to", socket.as_str()]);
+        }
+        args.extend(["focus-window", "--match", &matcher]);
+        runner.run("kitty", &args)
+    }
+}
+
+/// Finds the WezTerm pane whose title mentions the session and activates it
+pub struct WeztermBackend;
+
+impl TerminalBackend for WeztermBackend {
+    fn name(&self) -> &'static str {
+        "wezterm"
+    }
+
+    fn activate(&self, runner: &dyn CommandRunner, target: &MuxTarget) -> CommandOutput {
+        let session = target.title_hint();
+        let list = runner.run("wezterm", &["cli", "list", "--format", "json"]);
+        if !list.success {
+            return list;
+        }
+        let panes: Vec<serde_json::Value> = serde_json::from_str(&list.stdout).unwrap_or_default();
+        let pane_id = panes.iter().find_map(|pane| {
+            let title = pane.get("title")?.as_str()?;
+            if title.contains(session) {
+                pane.get("pane_id")?.as_u64()
+            } else {
+                None
+            }
+        });
+
+        match pane_id {
+            Some(id) => runner.run(
+                "wezterm",
+                &["cli", "activate-pane", "--pane-id", &id.to_string()],
+            ),
+            None => CommandOutput::failure(format!("no WezTerm pane titled {:?}", session)),
+        }
+    }
+}
+
+/// Raises any X11 window whose title mentions the session, via xdotool
+pub struct X11Backend;
+
+impl TerminalBackend for X11Backend {
+    fn name(&self) -> &'static str {
+        "x11"
+    }
+
+    fn activate(&self, runner: &dyn CommandRunner, target: &MuxTarget) -> CommandOutput {
+        runner.run(
+            "xdotool",
+            &[
+                "search",
+                "--limit",
+                "1",
+                "--name",
+                &regex::escape(target.title_hint()),
+                "windowactivate",
+            ],
+        )
+    }
+}
+
+/// Runs a user-defined profile in place of a built-in backend
+pub struct ProfileBackend {
+    pub profile: NavigationProfile,
+}
+
+/// `{name}` in a profile command
+static PLACEHOLDER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{(\w+)\}").unwrap());
+
+/// Fills in the placeholders in a single pass, so a value that happens to
+/// contain one is used verbatim. Unknown placeholders are left as they are.
+fn expand(template: &str, target: &MuxTarget) -> String {
+    let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+    PLACEHOLDER
+        .replace_all(template, |caps: &regex::Captures| match &caps[1] {
+            "session" => target.session.clone(),
+            "window" => target.window.clone(),
+            "pane" => target.pane.clone(),
+            "pane_id" => optional(&target.pane_id),
+            "socket" => optional(&target.socket),
+            "host" => optional(&target.ssh_target),
+            "project_path" => optional(&target.project_path),
+            "session_id" => optional(&target.session_id),
+            _ => caps[0].to_string(),
+        })
+        .into_owned()
+}
+
+impl TerminalBackend for ProfileBackend {
+    fn name(&self) -> &'static str {
+        "profile"
+    }
+
+    fn activate(&self, runner: &dyn CommandRunner, target: &MuxTarget) -> CommandOutput {
+        let mut stdout = String::new();
+        for (index, step) in self.profile.commands.iter().enumerate() {
+            let program = expand(&step.program, target);
+            let args: Vec<String> = step.args.iter().map(|arg| expand(arg, target)).collect();
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            let mut output =
+                runner.run_with_timeout(&program, &args, Duration::from_millis(step.timeout_ms));
+
+            let succeeded = output.code == Some(step.exit_code)
+                && step
+                    .stdout_contains
+                    .as_ref()
+                    .is_none_or(|text| output.stdout.contains(text.as_str()));
+            if succeeded {
+                stdout = output.stdout;
+            } else if !step.optional {
+                output.success = false;
+                output.stderr = format!(
+                    "profile {:?} step {} ({}) failed: {}",
+                    self.profile.name,
+                    index + 1,
+                    program,
+                    output.stderr.trim()
+                );
+                return output;
+            }
+        }
+        CommandOutput {
+            success: true,
+            code: Some(0),
+            stdout,
+            stderr: String::new(),
+        }
+    }
+
+    fn selects_pane(&self) -> bool {
+        self.profile.select_pane
+    }
+}
+
+pub fn backend_for(settings: &TerminalSettings) -> Box<dyn TerminalBackend + Send + Sync> {
+    let profile = settings
+        .profile
+        .as_ref()
+        .and_then(|name| settings.profiles.iter().find(|p| &p.name == name));
+    if let Some(profile) = profile {
+        return Box::new(ProfileBackend {
+            profile: profile.clone(),
+        });
+    }
+
+    match settings.backend {
+        TerminalBackendKind::Auto if cfg!(target_os = "macos") => Box::new(Iterm2Backend),
+        TerminalBackendKind::Auto => Box::new(TmuxBackend),
+        TerminalBackendKind::Iterm2 => Box::new(Iterm2Backend),
+        TerminalBackendKind::Tmux => Box::new(TmuxBackend),
+        TerminalBackendKind::Kitty => Box::new(KittyBackend {
+            socket: settings.kitty_socket.clone(),
+        }),
+        TerminalBackendKind::Wezterm => Box::new(WeztermBackend),
+        TerminalBackendKind::X11 => Box::new(X11Backend),
+    }
+}
+
+// ============================================================================
+// Navigation
+// ============================================================================
+
+/// What `navigate` managed to do, so callers can tell a stale target from a
+/// terminal that merely failed to come to the front
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct NavigationResult {
+    pub ok: bool,
+    pub terminal_focused: bool,
+    pub session_found: bool,
+    pub window_found: bool,
+    pub pane_found: bool,
+    pub stderr: String,
+}
+
+impl NavigationResult {
+    /// Short human-readable reason for a failed navigation
+    pub fn error_message(&self) -> Option<String> {
+        if self.ok {
+            return None;
+        }
+        let reason = if !self.session_found {
+            "session not found"
+        } else if !self.window_found {
+            "window not found"
+        } else if !self.pane_found {
+            "pane not found"
+        } else {
+            "navigation failed"
+        };
+        let detail = self.stderr.lines().next().unwrap_or("").trim();
+        Some(if detail.is_empty() {
+            reason.to_string()
+        } else {
+            format!("{}: {}", reason, detail)
+        })
+    }
+
+    fn finish(mut self, stderr: Vec<String>) -> Self {
+        self.ok = self.session_found && self.window_found && self.pane_found;
+        self.stderr = join_stderr(stderr);
+        self
+    }
+}
+
+/// Focuses the terminal for the target's session, then selects its window
+/// and pane in whichever multiplexer it runs in. Empty `window`/`pane` are
+/// skipped and count as found.
+pub fn navigate(
+    backend: &dyn TerminalBackend,
+    runner: &dyn CommandRunner,
+    target: &MuxTarget,
+) -> NavigationResult {
+    match target.kind {
+        MuxKind::Tmux => navigate_tmux(backend, runner, target),
+        MuxKind::Zellij => navigate_zellij(backend, runner, target),
+        MuxKind::Screen => navigate_screen(backend, runner, target),
+    }
+}
+
+/// Brings the terminal to the front once the session is known to exist.
+/// Returns false when the backend also selected the window and pane, so
+/// there is nothing left to do.
+fn focus_terminal(
+    backend: &dyn TerminalBackend,
+    runner: &dyn CommandRunner,
+    target: &MuxTarget,
+    result: &mut NavigationResult,
+    stderr: &mut Vec<String>,
+) -> bool {
+    if !backend.handles(target.kind) {
+        return true;
+    }
+
+    let focus = backend.activate(runner, target);
+    result.terminal_focused = focus.success;
+    if !focus.success {
+        println!(
+            "[Lovnotifier] {} could not focus session {}: {}",
+            backend.name(),
+            target.session,
+            focus.stderr.trim()
+        );
+        stderr.push(focus.stderr);
+    }
+
+    if !backend.selects_pane() {
+        result.window_found = focus.success;
+        result.pane_found = focus.success;
+        return false;
+    }
+    true
+}
+
+/// A tmux pane id is resolved first; if it no longer exists the coordinates
+/// are used instead
+fn navigate_tmux(
+    backend: &dyn TerminalBackend,
+    runner: &dyn CommandRunner,
+    target: &MuxTarget,
+) -> NavigationResult {
+    let mut result = NavigationResult::default();
+    let mut stderr = Vec::new();
+    let socket = target.socket.as_deref();
+    let mut target = target.clone();
+
+    if let Some(pane_id) = target.pane_id.clone() {
+        match resolve_pane_id(runner, socket, &pane_id) {
+            Ok((session, window, pane)) => {
+                target.session = session;
+                target.window = window;
+                target.pane = pane;
+            }
+            Err(e) => {
+                // The pane is gone; its old coordinates may now hold another
+                result.session_found = true;
+                result.window_found = true;
+                stderr.push(e);
+                return result.finish(stderr);
+            }
+        }
+    }
+
+    // "=" makes tmux match the session name exactly instead of by prefix
+    let has_session = run_tmux(
+        runner,
+        socket,
+        &["has-session", "-t", &format!("={}", target.session)],
+    );
+    result.session_found = !target.session.is_empty() && has_session.success;
+    if !result.session_found {
+        stderr.push(has_session.stderr);
+        return result.finish(stderr);
+    }
+
+    if !focus_terminal(backend, runner, &target, &mut result, &mut stderr) {
+        return result.finish(stderr);
+    }
+
+    // A resolved pane id is the most precise target for both commands
+    let window_target = match &target.pane_id {
+        Some(pane_id) => pane_id.clone(),
+        None => format!("{}:{}", target.session, target.window),
+    };
+    let pane_target = match &target.pane_id {
+        Some(pane_id) => pane_id.clone(),
+        None => format!("{}:{}.{}", target.session, target.window, target.pane),
+    };
+
+    result.window_found = target.window.is_empty() || {
+        let output = run_tmux(runner, socket, &["select-window", "-t", &window_target]);
+        stderr.push(output.stderr);
+        output.success
+    };
+    result.pane_found = result.window_found
+        && (target.pane.is_empty() || {
+            let output = run_tmux(runner, socket, &["select-pane", "-t", &pane_target]);
+            stderr.push(output.stderr);
+            output.success
+        });
+
+    result.finish(stderr)
+}
+
+/// Most `focus-next-pane` steps taken while looking for a zellij pane
+const ZELLIJ_MAX_PANE_STEPS: usize = 32;
+
+/// The pane focused by the first client attached to `session`, as listed by
+/// `list-clients` (e.g. `terminal_3`)
+fn zellij_focused_pane(runner: &dyn CommandRunner, session: &str) -> Result<String, String> {
+    let output = runner.run("zellij", &["--session", session, "action", "list-clients"]);
+    if !output.success {
+        return Err(output.stderr);
+    }
+    output
+        .stdout
+        .lines()
+        .skip(1)
+        .find_map(|line| line.split_whitespace().nth(1))
+        .map(str::to_string)
+        .ok_or_else(|| format!("no client attached to zellij session {}", session))
+}
+
+/// Cycles focus through the panes of the current tab until `pane` has it.
+/// The zellij CLI cannot focus a pane by id, so the starting pane is
+/// recorded and the search gives up once focus comes back around to it.
+fn focus_zellij_pane(
+    runner: &dyn CommandRunner,
+    session: &str,
+    pane: &str,
+    stderr: &mut Vec<String>,
+) -> bool {
+    // `$ZELLIJ_PANE_ID` is the bare number of a terminal pane
+    let wanted = if pane.parse::<u32>().is_ok() {
+        format!("terminal_{}", pane)
+    } else {
+        pane.to_string()
+    };
+
+    let start = match zellij_focused_pane(runner, session) {
+        Ok(start) => start,
+        Err(e) => {
+            stderr.push(e);
+            return false;
+        }
+    };
+    let mut current = start.clone();
+    for _ in 0..ZELLIJ_MAX_PANE_STEPS {
+        if current == wanted {
+            return true;
+        }
+        let output = runner.run(
+            "zellij",
+            &["--session", session, "action", "focus-next-pane"],
+        );
+        if !output.success {
+            stderr.push(output.stderr);
+            return false;
+        }
+        current = match zellij_focused_pane(runner, session) {
+            Ok(current) => current,
+            Err(e) => {
+                stderr.push(e);
+                return false;
+            }
+        };
+        if current == start {
+            break;
+        }
+    }
+    stderr.push(format!("no pane {} in the current tab", wanted));
+    false
+}
+
+/// `window` is the tab position (1-based) or name, `pane` the pane id from
+/// `$ZELLIJ_PANE_ID`
+fn navigate_zellij(
+    backend: &dyn TerminalBackend,
+    runner: &dyn CommandRunner,
+    target: &MuxTarget,
+) -> NavigationResult {
+    let mut result = NavigationResult::default();
+    let mut stderr = Vec::new();
+
+    let sessions = runner.run("zellij", &["list-sessions", "--short", "--no-formatting"]);
+    result.session_found = !target.session.is_empty()
+        && sessions.success
+        && sessions
+            .stdout
+            .lines()
+            .any(|line| line.trim() == target.session);
+    if !result.session_found {
+        stderr.push(sessions.stderr);
+        return result.finish(stderr);
+    }
+
+    if !focus_terminal(backend, runner, target, &mut result, &mut stderr) {
+        return result.finish(stderr);
+    }
+
+    result.window_found = target.window.is_empty() || {
+        let action = if target.window.parse::<u32>().is_ok() {
+            "go-to-tab"
+        } else {
+            "go-to-tab-name"
+        };
+        let output = runner.run(
+            "zellij",
+            &[
+                "--session",
+                &target.session,
+                "action",
+                action,
+                &target.window,
+            ],
+        );
+        stderr.push(output.stderr);
+        output.success
+    };
+    result.pane_found = result.window_found
+        && (target.pane.is_empty()
+            || focus_zellij_pane(runner, &target.session, &target.pane, &mut stderr));
+
+    result.finish(stderr)
+}
+
+/// `session` may be the bare name or `pid.name` as in `$STY`. screen regions
+/// are not addressable from outside, so navigation ends at the window and a
+/// requested pane counts as not found.
+fn navigate_screen(
+    backend: &dyn TerminalBackend,
+    runner: &dyn CommandRunner,
+    target: &MuxTarget,
+) -> NavigationResult {
+    let mut result = NavigationResult::default();
+    let mut stderr = Vec::new();
+
+    // `screen -ls` exits non-zero even when it lists sessions, so only the
+    // listing itself is trusted
+    let sessions = runner.run("screen", &["-ls"]);
+    result.session_found = !target.session.is_empty()
+        && sessions.stdout.lines().any(|line| {
+            let name = line.split_whitespace().next().unwrap_or("");
+            name == target.session
+                || name
+                    .split_once('.')
+                    .is_some_and(|(_, name)| name == target.session)
+        });
+    if !result.session_found {
+        stderr.push(sessions.stderr);
+        return result.finish(stderr);
+    }
+
+    if !focus_terminal(backend, runner, target, &mut result, &mut stderr) {
+        return result.finish(stderr);
+    }
+
+    result.window_found = target.window.is_empty() || {
+        let output = runner.run(
+            "screen",
+            &["-S", &target.session, "-X", "select", &target.window],
+        );
+        stderr.push(output.stderr);
+        output.success
+    };
+    result.pane_found = result.window_found && target.pane.is_empty();
+    if result.window_found && !result.pane_found {
+        stderr.push("screen cannot select a region from outside".to_string());
+    }
+
+    result.finish(stderr)
+}
+
+fn join_stderr(parts: Vec<String>) -> String {
+    parts
+        .iter()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+
+    /// Records every invocation and answers with queued outputs, or with a
+    /// bare success once the queue is empty
+    #[derive(Default)]
+    struct FakeRunner {
+        calls: RefCell<Vec<Vec<String>>>,
+        outputs: RefCell<VecDeque<CommandOutput>>,
+        timeouts: RefCell<Vec<Duration>>,
+    }
+
+    impl FakeRunner {
+        fn with(outputs: Vec<CommandOutput>) -> Self {
+            Self {
+                outputs: RefCell::new(outputs.into()),
+                ..Self::default()
+            }
+        }
+
+        fn calls(&self) -> Vec<String> {
+            self.calls
+                .borrow()
+                .iter()
+                .map(|argv| argv.join(" "))
+                .collect()
+        }
+    }
+
+    impl CommandRunner for FakeRunner {
+        fn run(&self, program: &str, args: &[&str]) -> CommandOutput {
+            let mut argv = vec![program.to_string()];
+            argv.extend(args.iter().map(|arg| arg.to_string()));
+            self.calls.borrow_mut().push(argv);
+            self.outputs.borrow_mut().pop_front().unwrap_or_else(ok)
+        }
+
+        fn run_with_timeout(
+            &self,
+            program: &str,
+            args: &[&str],
+            timeout: Duration,
+        ) -> CommandOutput {
+            self.timeouts.borrow_mut().push(timeout);
+            self.run(program, args)
+        }
+    }
+
+    fn ok() -> CommandOutput {
+        CommandOutput {
+            success: true,
+            code: Some(0),
+            ..CommandOutput::default()
+        }
+    }
+
+    fn stdout(text: &str) -> CommandOutput {
+        CommandOutput {
+            stdout: text.to_string(),
+            ..ok()
+        }
+    }
+
+    fn failed(stderr: &str) -> CommandOutput {
+        CommandOutput {
+            success: false,
+            code: Some(1),
+            stderr: stderr.to_string(),
+            ..CommandOutput::default()
+        }
+    }
+
+    fn target(session: &str) -> MuxTarget {
+        MuxTarget {
+            session: session.to_string(),
+            window: "2".to_string(),
+            pane: "1".to_string(),
+            ..MuxTarget::default()
+        }
+    }
+
+    #[test]
+    fn iterm2_needs_the_session_to_be_found() {
+        let runner = FakeRunner::with(vec![stdout("FOUND\n")]);
+        let output = Iterm2Backend.activate(&runner, &target("say \"hi\""));
+        assert!(output.success);
+        let calls = runner.calls.borrow();
+        assert_eq!(calls[0][..2], ["osascript", "-e"]);
+        assert!(calls[0][2].contains(r#"if name of s contains "say \"hi\"" then"#));
+
+        let runner = FakeRunner::with(vec![stdout("NOT_FOUND\n")]);
+        assert!(!Iterm2Backend.activate(&runner, &target("api")).success);
+        let runner = FakeRunner::with(vec![failed("iTerm2 is not running")]);
+        assert!(!Iterm2Backend.activate(&runner, &target("api")).success);
+    }
+
+    #[test]
+    fn tmux_backend_switches_the_client() {
+        let runner = FakeRunner::default();
+        let mut target = target("api");
+        target.socket = Some("work".to_string());
+        assert!(TmuxBackend.activate(&runner, &target).success);
+        assert_eq!(runner.calls(), ["tmux -L work switch-client -t api"]);
+
+        let runner = FakeRunner::with(vec![failed("no current client")]);
+        let output = TmuxBackend.activate(&runner, &target);
+        assert!(!output.success);
+        assert_eq!(output.stderr, "no current client");
+        assert!(!TmuxBackend.handles(MuxKind::Zellij));
+    }
+
+    #[test]
+    fn kitty_matches_the_escaped_title() {
+        let backend = KittyBackend {
+            socket: Some("unix:/tmp/kitty".to_string()),
+        };
+        let runner = FakeRunner::default();
+        assert!(backend.activate(&runner, &target("web.api")).success);
+        assert_eq!(
+            runner.calls(),
+            ["kitty @ --to unix:/tmp/kitty focus-window --match title:web\\.api"]
+        );
+
+        let backend = KittyBackend { socket: None };
+        let runner = FakeRunner::with(vec![failed("No matching windows")]);
+        assert!(!backend.activate(&runner, &target("api")).success);
+        assert_eq!(runner.calls(), ["kitty @ focus-window --match title:api"]);
+    }
+
+    #[test]
+    fn wezterm_activates_the_pane_with_a_matching_title() {
+        let list = r#"[
+            {"pane_id": 3, "title": "zsh"},
+            {"pane_id": 7, "title": "api - tmux"}
+        ]"#;
+        let runner = FakeRunner::with(vec![stdout(list)]);
+        assert!(WeztermBackend.activate(&runner, &target("api")).success);
+        assert_eq!(
+            runner.calls(),
+            [
+                "wezterm cli list --format json",
+                "wezterm cli activate-pane --pane-id 7"
+            ]
+        );
+
+        let runner = FakeRunner::with(vec![stdout(list)]);
+        let output = WeztermBackend.activate(&runner, &target("db"));
+        assert!(!output.success);
+        assert_eq!(output.stderr, "no WezTerm pane titled \"db\"");
+        assert_eq!(runner.calls().len(), 1);
+
+        let runner = FakeRunner::with(vec![failed("no running wezterm")]);
+        assert!(!WeztermBackend.activate(&runner, &target("api")).success);
+        assert_eq!(runner.calls().len(), 1);
+    }
+
+    #[test]
+    fn x11_searches_by_escaped_title() {
+        let runner = FakeRunner::default();
+        let mut target = target("api");
+        target.ssh_target = Some("deploy@build.local".to_string());
+        assert!(X11Backend.activate(&runner, &target).success);
+        assert_eq!(
+            runner.calls(),
+            ["xdotool search --limit 1 --name build\\.local windowactivate"]
+        );
+
+        let runner = FakeRunner::with(vec![failed("")]);
+        assert!(!X11Backend.activate(&runner, &target).success);
+    }
+
+    #[test]
+    fn navigate_tmux_prefers_the_pane_id() {
+        let runner = FakeRunner::with(vec![stdout("api\t4\t0\n")]);
+        let mut target = target("old");
+        target.pane_id = Some("%12".to_string());
+        let result = navigate(&TmuxBackend, &runner, &target);
+        assert!(result.ok);
+        assert!(result.terminal_focused);
+        assert_eq!(
+            runner.calls(),
+            [
+                "tmux display-message -p -t %12 #{session_name}\t#{window_index}\t#{pane_index}",
+                "tmux has-session -t =api",
+                "tmux switch-client -t api",
+                "tmux select-window -t %12",
+                "tmux select-pane -t %12",
+            ]
+        );
+    }
+
+    #[test]
+    fn navigate_tmux_does_not_fall_back_from_a_missing_pane_id() {
+        let runner = FakeRunner::with(vec![failed("can't find pane: %12")]);
+        let mut target = target("api");
+        target.pane_id = Some("%12".to_string());
+        target.socket = Some("/tmp/tmux-1000/work".to_string());
+        let result = navigate(&TmuxBackend, &runner, &target);
+        assert!(!result.ok);
+        assert!(!result.pane_found);
+        assert_eq!(
+            result.error_message().as_deref(),
+            Some("pane not found: can't find pane: %12")
+        );
+        assert!(runner
+            .calls()
+            .iter()
+            .all(|call| !call.contains("select-pane") && !call.contains("switch-client")));
+    }
+
+    #[test]
+    fn navigate_tmux_reports_a_missing_session() {
+        let runner = FakeRunner::with(vec![failed("can't find session: api")]);
+        let result = navigate(&TmuxBackend, &runner, &target("api"));
+        assert!(!result.ok);
+        assert!(!result.session_found);
+        assert_eq!(
+            result.error_message().as_deref(),
+            Some("session not found: can't find session: api")
+        );
+        assert_eq!(runner.calls(), ["tmux has-session -t =api"]);
+    }
+
+    #[test]
+    fn navigate_tmux_selects_the_pane_when_focusing_fails() {
+        let runner = FakeRunner::with(vec![ok(), failed("no current client")]);
+        let result = navigate(&TmuxBackend, &runner, &target("api"));
+        assert!(result.ok);
+        assert!(!result.terminal_focused);
+        assert_eq!(result.stderr, "no current client");
+
+        let runner = FakeRunner::with(vec![ok(), ok(), failed("can't find window: 2")]);
+        let result = navigate(&TmuxBackend, &runner, &target("api"));
+        assert!(!result.ok);
+        assert!(!result.window_found);
+        assert_eq!(
+            result.error_message().as_deref(),
+            Some("window not found: can't find window: 2")
+        );
+        assert_eq!(runner.calls().len(), 3);
+    }
+
+    #[test]
+    fn capture_pane_keeps_the_last_lines_within_the_timeout() {
+        let runner = FakeRunner::with(vec![stdout("one\ntwo\n\x1b[31mthree\x1b[0m  \n\n\n")]);
+        let mut target = target("api");
+        target.pane_id = Some("%7".to_string());
+        assert_eq!(
+            capture_pane(&runner, &target, 2).as_deref(),
+            Some("two\nthree")
+        );
+        assert_eq!(runner.calls(), ["tmux capture-pane -p -J -t %7 -S -2"]);
+        assert_eq!(*runner.timeouts.borrow(), [TMUX_QUERY_TIMEOUT]);
+
+        let runner = FakeRunner::with(vec![failed("tmux timed out after 2s")]);
+        assert_eq!(capture_pane(&runner, &target, 2), None);
+    }
+
+    fn zellij_target(pane: &str) -> MuxTarget {
+        MuxTarget {
+            kind: MuxKind::Zellij,
+            pane: pane.to_string(),
+            ..target("api")
+        }
+    }
+
+    fn zellij_clients(pane: &str) -> CommandOutput {
+        stdout(&format!(
+            "CLIENT_ID ZELLIJ_PANE_ID RUNNING_COMMAND\n1 {} zsh\n",
+            pane
+        ))
+    }
+
+    #[test]
+    fn navigate_zellij_cycles_to_the_pane() {
+        let runner = FakeRunner::with(vec![
+            stdout("web\napi\n"),
+            ok(),
+            zellij_clients("terminal_1"),
+            ok(),
+            zellij_clients("terminal_3"),
+        ]);
+        let result = navigate(&TmuxBackend, &runner, &zellij_target("3"));
+        assert!(result.ok);
+        assert!(result.pane_found);
+        assert_eq!(
+            runner.calls(),
+            [
+                "zellij list-sessions --short --no-formatting",
+                "zellij --session api action go-to-tab 2",
+                "zellij --session api action list-clients",
+                "zellij --session api action focus-next-pane",
+                "zellij --session api action list-clients",
+            ]
+        );
+    }
+
+    #[test]
+    fn navigate_zellij_gives_up_once_focus_wraps_around() {
+        let runner = FakeRunner::with(vec![
+            stdout("api\n"),
+            ok(),
+            zellij_clients("terminal_1"),
+            ok(),
+            zellij_clients("terminal_2"),
+            ok(),
+            zellij_clients("terminal_1"),
+        ]);
+        let mut target = zellij_target("9");
+        target.window = "logs".to_string();
+        let result = navigate(&TmuxBackend, &runner, &target);
+        assert!(!result.ok);
+        assert!(result.window_found);
+        assert!(!result.pane_found);
+        assert_eq!(
+            result.error_message().as_deref(),
+            Some("pane not found: no pane terminal_9 in the current tab")
+        );
+        assert_eq!(
+            runner.calls()[1],
+            "zellij --session api action go-to-tab-name logs"
+        );
+        assert_eq!(runner.calls().len(), 7);
+    }
+
+    #[test]
+    fn navigate_zellij_without_a_pane_stops_at_the_tab() {
+        let runner = FakeRunner::with(vec![stdout("api\n"), failed("no tab 2")]);
+        let result = navigate(&TmuxBackend, &runner, &zellij_target(""));
+        assert!(!result.ok);
+        assert!(!result.window_found);
+        assert_eq!(runner.calls().len(), 2);
+
+        let runner = FakeRunner::with(vec![stdout("api\n")]);
+        let result = navigate(&TmuxBackend, &runner, &zellij_target(""));
+        assert!(result.ok);
+        assert_eq!(runner.calls().len(), 2);
+    }
+
+    #[test]
+    fn navigate_zellij_reports_a_missing_session() {
+        let runner = FakeRunner::with(vec![stdout("web\n")]);
+        let result = navigate(&TmuxBackend, &runner, &zellij_target("3"));
+        assert!(!result.session_found);
+        assert_eq!(runner.calls().len(), 1);
+    }
+
+    fn screen_target(pane: &str) -> MuxTarget {
+        MuxTarget {
+            kind: MuxKind::Screen,
+            pane: pane.to_string(),
+            ..target("api")
+        }
+    }
+
+    #[test]
+    fn navigate_screen_selects_the_window() {
+        let sessions = "There is a screen on:\n\t4242.api\t(Attached)\n1 Socket in /run/screen.\n";
+        let runner = FakeRunner::with(vec![CommandOutput {
+            success: false,
+            ..stdout(sessions)
+        }]);
+        let result = navigate(&TmuxBackend, &runner, &screen_target(""));
+        assert!(result.ok);
+        assert_eq!(runner.calls(), ["screen -ls", "screen -S api -X select 2"]);
+    }
+
+    #[test]
+    fn navigate_screen_cannot_select_a_region() {
+        let runner = FakeRunner::with(vec![stdout("\t4242.api\t(Detached)\n")]);
+        let result = navigate(&TmuxBackend, &runner, &screen_target("1"));
+        assert!(!result.ok);
+        assert!(result.window_found);
+        assert!(!result.pane_found);
+        assert_eq!(
+            result.error_message().as_deref(),
+            Some("pane not found: screen cannot select a region from outside")
+        );
+
+        let runner = FakeRunner::with(vec![stdout("\t4242.web\t(Detached)\n")]);
+        let result = navigate(&TmuxBackend, &runner, &screen_target(""));
+        assert!(!result.session_found);
+    }
+
+    fn ssh_command() -> Vec<String> {
+        ["ssh", "-o", "BatchMode=yes"].map(String::from).to_vec()
+    }
+
+    #[test]
+    fn ssh_runner_quotes_the_remote_command() {
+        let inner = FakeRunner::default();
+        let command = ssh_command();
+        let ssh = SshRunner {
+            inner: &inner,
+            command: &command,
+            target: "deploy@build",
+        };
+        let output = run_tmux(
+            &ssh,
+            Some("my socket"),
+            &["has-session", "-t", "=it's $HOME"],
+        );
+        assert!(output.success);
+        assert_eq!(
+            *inner.calls.borrow(),
+            [[
+                "ssh",
+                "-o",
+                "BatchMode=yes",
+                "deploy@build",
+                r#"'tmux' '-L' 'my socket' 'has-session' '-t' '=it'\''s $HOME'"#,
+            ]]
+        );
+        assert_eq!(*inner.timeouts.borrow(), [REMOTE_TIMEOUT]);
+    }
+
+    #[test]
+    fn ssh_runner_keeps_other_programs_local() {
+        let inner = FakeRunner::default();
+        let command = ssh_command();
+        let ssh = SshRunner {
+            inner: &inner,
+            command: &command,
+            target: "build",
+        };
+        ssh.run("osascript", &["-e", "activate"]);
+        ssh.run_with_timeout("screen", &["-ls"], Duration::from_secs(2));
+        assert_eq!(
+            inner.calls(),
+            [
+                "osascript -e activate",
+                "ssh -o BatchMode=yes build 'screen' '-ls'"
+            ]
+        );
+        assert_eq!(*inner.timeouts.borrow(), [Duration::from_secs(2)]);
+    }
+
+    #[test]
+    fn ssh_runner_passes_failures_through() {
+        let inner = FakeRunner::with(vec![CommandOutput {
+            code: Some(255),
+            stderr: "ssh: connect to host build port 22: Connection refused".to_string(),
+            ..CommandOutput::default()
+        }]);
+        let command = ssh_command();
+        let ssh = SshRunner {
+            inner: &inner,
+            command: &command,
+            target: "build",
+        };
+        let output = ssh.run("tmux", &["list-clients"]);
+        assert!(!output.success);
+        assert_eq!(output.code, Some(255));
+
+        let ssh = SshRunner {
+            inner: &inner,
+            command: &[],
+            target: "build",
+        };
+        let output = ssh.run("tmux", &["list-clients"]);
+        assert!(!output.success);
+        assert_eq!(output.stderr, "terminal.ssh_command is empty");
+        assert_eq!(inner.calls().len(), 1);
+    }
+
+    fn step(program: &str, args: &[&str]) -> CommandTemplate {
+        CommandTemplate {
+            program: program.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            ..CommandTemplate::default()
+        }
+    }
+
+    fn profile(commands: Vec<CommandTemplate>) -> ProfileBackend {
+        ProfileBackend {
+            profile: NavigationProfile {
+                name: "ghostty".to_string(),
+                commands,
+                ..NavigationProfile::default()
+            },
+        }
+    }
+
+    #[test]
+    fn expand_substitutes_each_placeholder_once() {
+        let mut target = target("{project_path}");
+        target.pane_id = Some("%3".to_string());
+        target.project_path = Some("/src/app".to_string());
+        assert_eq!(
+            expand(
+                "{session}:{window}.{pane} {pane_id} {project_path}",
+                &target
+            ),
+            "{project_path}:2.1 %3 /src/app"
+        );
+        assert_eq!(expand("{socket}{unknown}", &target), "{unknown}");
+    }
+
+    #[test]
+    fn profile_runs_expanded_steps_with_their_timeouts() {
+        let mut first = step("open", &["-a", "Ghostty", "{project_path}"]);
+        first.timeout_ms = 1500;
+        let backend = profile(vec![
+            first,
+            step("tmux", &["switch-client", "-t", "{session}"]),
+        ]);
+        let runner = FakeRunner::with(vec![ok(), stdout("switched")]);
+        let mut target = target("api");
+        target.project_path = Some("/src/api".to_string());
+
+        let output = backend.activate(&runner, &target);
+        assert!(output.success);
+        assert_eq!(output.stdout, "switched");
+        assert_eq!(
+            runner.calls(),
+            ["open -a Ghostty /src/api", "tmux switch-client -t api"]
+        );
+        assert_eq!(
+            *runner.timeouts.borrow(),
+            [Duration::from_millis(1500), Duration::from_millis(5000)]
+        );
+    }
+
+    #[test]
+    fn profile_checks_exit_code_and_stdout() {
+        let mut check = step("pgrep", &["-x", "Ghostty"]);
+        check.exit_code = 1;
+        let backend = profile(vec![check]);
+        assert!(
+            backend
+                .activate(&FakeRunner::with(vec![failed("")]), &target("api"))
+                .success
+        );
+        let output = backend.activate(&FakeRunner::default(), &target("api"));
+        assert!(!output.success);
+        assert_eq!(output.stderr, "profile \"ghostty\" step 1 (pgrep) failed: ");
+
+        let mut find = step("osascript", &["-e", "find"]);
+        find.stdout_contains = Some("FOUND".to_string());
+        let backend = profile(vec![find]);
+        let runner = FakeRunner::with(vec![stdout("FOUND\n")]);
+        assert!(backend.activate(&runner, &target("api")).success);
+        let runner = FakeRunner::with(vec![stdout("nothing")]);
+        assert!(!backend.activate(&runner, &target("api")).success);
+    }
+
+    #[test]
+    fn profile_stops_at_the_first_required_failure() {
+        let mut optional = step("wmctrl", &["-a", "{session}"]);
+        optional.optional = true;
+        let backend = profile(vec![
+            optional,
+            step("xdotool", &["key", "ctrl+b"]),
+            step("true", &[]),
+        ]);
+
+        let runner = FakeRunner::with(vec![failed("no window"), ok()]);
+        assert!(backend.activate(&runner, &target("api")).success);
+        assert_eq!(runner.calls().len(), 3);
+
+        let runner = FakeRunner::with(vec![ok(), failed("no display")]);
+        let output = backend.activate(&runner, &target("api"));
+        assert!(!output.success);
+        assert_eq!(
+            output.stderr,
+            "profile \"ghostty\" step 2 (xdotool) failed: no display"
+        );
+        assert_eq!(runner.calls().len(), 2);
+    }
+}
@@ -0,0 +1,300 @@
+use crate::terminal::{
+    is_server_unreachable, run_tmux, run_tmux_with_timeout, CommandRunner, TMUX_QUERY_TIMEOUT,
+};
+use crate::NotifyPayload;
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+
+/// Header carrying the socket path of the tmux server a hook fired in
+pub const SOCKET_HEADER: &str = "x-lovnotifier-tmux-socket";
+
+/// tmux events that raise a notification
+pub const HOOKS: [&str; 4] = ["alert-bell", "alert-activity", "alert-silence", "pane-died"];
+
+/// tmux hooks are arrays; our commands live at this index so hooks the user
+/// set up themselves stay untouched. Any index works, the port is merely an
+/// unlikely one to collide.
+const HOOK_INDEX: u16 = crate::NOTIFY_SERVER_PORT;
+
+const CONFIG_BEGIN: &str = "# >>> lovnotifier hooks >>>";
+const CONFIG_END: &str = "# <<< lovnotifier hooks <<<";
+
+#[derive(Debug, Serialize, Default)]
+pub struct HooksReport {
+    /// Whether the running tmux server was updated
+    pub server: bool,
+    /// tmux.conf that was updated, if any
+    pub config: Option<String>,
+    pub errors: Vec<String>,
+}
+
+/// What the server looks up for the pane a hook fired in
+const PANE_FORMAT: &str =
+    "#{session_name}\t#{window_index}\t#{window_name}\t#{pane_index}\t#{pane_dead_status}";
+
+fn title(hook: &str, place: &str, dead_status: &str) -> String {
+    match hook {
+        "alert-bell" => format!("Bell in {}", place),
+        "alert-activity" => format!("Activity in {}", place),
+        "alert-silence" => format!("Silence in {}", place),
+        _ if dead_status.is_empty() => format!("Pane exited in {}", place),
+        _ => format!("Pane exited ({}) in {}", dead_status, place),
+    }
+}
+
+/// Double-quotes `value` for the tmux command parser
+fn tmux_quote(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('$', "\\$")
+    )
+}
+
+/// The tmux command a hook runs. `run-shell` expands the formats before the
+/// shell sees them, so the JSON payload only carries the pane id, which never
+/// needs quoting. The socket path may contain anything, so it goes in a
+/// header, escaped for the shell by `q:`. Session and window names could
+/// break out of the quotes; `complete_payload` looks them up instead.
+pub fn hook_command(hook: &str) -> String {
+    let payload = format!(
+        concat!(
+            r#"{{"title":"{}","source":"tmux","tags":["{}"],"tmux_hook":"{}","#,
+            r##""tmux_pane_id":"#{{pane_id}}"}}"##
+        ),
+        title(hook, "tmux", ""),
+        hook,
+        hook
+    );
+    let shell = format!(
+        "curl -s -m 2 -X POST -H 'Content-Type: application/json' \
+         -H {}:#{{q:socket_path}} -d '{}' http://127.0.0.1:{}/notify >/dev/null 2>&1",
+        SOCKET_HEADER,
+        payload,
+        crate::NOTIFY_SERVER_PORT
+    );
+    format!("run-shell -b {}", tmux_quote(&shell))
+}
+
+/// Fills in the session, window and pane a hook fired in and names them in
+/// the title. Without an answer from tmux the hook's generic title stays.
+pub fn complete_payload(runner: &dyn CommandRunner, payload: &mut NotifyPayload) {
+    let (Some(hook), Some(pane_id)) = (payload.tmux_hook.clone(), payload.tmux_pane_id.clone())
+    else {
+        return;
+    };
+    let output = run_tmux_with_timeout(
+        runner,
+        payload.tmux_socket.as_deref(),
+        &["display-message", "-p", "-t", &pane_id, PANE_FORMAT],
+        TMUX_QUERY_TIMEOUT,
+    );
+    let line = output.stdout.trim_end_matches('\n');
+    let fields: Vec<&str> = line.split('\t').collect();
+    let [session, window_index, window_name, pane_index, dead_status] = fields[..] else {
+        println!(
+            "[Lovnotifier] Cannot look up tmux pane {} for {}: {}",
+            pane_id,
+            hook,
+            output.stderr.trim()
+        );
+        return;
+    };
+    payload.title = title(&hook, &format!("{}:{}", session, window_name), dead_status);
+    payload.tmux_session = Some(session.to_string());
+    payload.tmux_window = Some(window_index.to_string());
+    payload.tmux_pane = Some(pane_index.to_string());
+}
+
+fn hook_slot(hook: &str) -> String {
+    format!("{}[{}]", hook, HOOK_INDEX)
+}
+
+/// `~/.config/tmux/tmux.conf` when it exists (tmux 3.1+), else `~/.tmux.conf`
+fn get_config_path() -> Option<PathBuf> {
+    let xdg = dirs::config_dir().map(|dir| dir.join("tmux").join("tmux.conf"));
+    match xdg {
+        Some(path) if path.exists() => Some(path),
+        _ => dirs::home_dir().map(|home| home.join(".tmux.conf")),
+    }
+}
+
+/// Removes our marked block from a tmux.conf
+fn strip_block(content: &str) -> String {
+    let mut kept = Vec::new();
+    let mut inside = false;
+    for line in content.lines() {
+        if line.trim() == CONFIG_BEGIN {
+            inside = true;
+        } else if line.trim() == CONFIG_END {
+            inside = false;
+        } else if !inside {
+            kept.push(line);
+        }
+    }
+    let mut result = kept.join("\n");
+    if !result.is_empty() {
+        result.push('\n');
+    }
+    result
+}
+
+fn config_block() -> String {
+    let mut block = vec![CONFIG_BEGIN.to_string()];
+    for hook in HOOKS {
+        block.push(format!(
+            "set-hook -g {} {}",
+            hook_slot(hook),
+            tmux_quote(&hook_command(hook))
+        ));
+    }
+    block.push(CONFIG_END.to_string());
+    block.join("\n") + "\n"
+}
+
+fn update_config(install: bool, report: &mut HooksReport) {
+    let Some(path) = get_config_path() else {
+        report.errors.push("cannot locate tmux.conf".to_string());
+        return;
+    };
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+    let mut content = strip_block(&existing);
+    if install {
+        if !content.is_empty() && !content.ends_with("\n\n") {
+            content.push('\n');
+        }
+        content.push_str(&config_block());
+    } else if content == existing || (existing.is_empty() && !path.exists()) {
+        return;
+    }
+
+    match fs::write(&path, content) {
+        Ok(()) => report.config = Some(path.to_string_lossy().into_owned()),
+        Err(e) => report.errors.push(format!("{}: {}", path.display(), e)),
+    }
+}
+
+fn update_server(
+    runner: &dyn CommandRunner,
+    socket: Option<&str>,
+    install: bool,
+    report: &mut HooksReport,
+) {
+    let mut ok = true;
+    for hook in HOOKS {
+        let slot = hook_slot(hook);
+        let output = if install {
+            run_tmux(
+                runner,
+                socket,
+                &["set-hook", "-g", &slot, &hook_command(hook)],
+            )
+        } else {
+            run_tmux(runner, socket, &["set-hook", "-gu", &slot])
+        };
+        if !output.success {
+            ok = false;
+            // Nothing to remove from a server that is not running
+            if !install && is_server_unreachable(&output.stderr) {
+                break;
+            }
+            report
+                .errors
+                .push(format!("{}: {}", hook, output.stderr.trim()));
+        }
+    }
+    report.server = ok;
+}
+
+/// Installs the hooks into the running tmux server and, with `persist`,
+/// into tmux.conf so new servers get them too
+pub fn install(runner: &dyn CommandRunner, socket: Option<&str>, persist: bool) -> HooksReport {
+    let mut report = HooksReport::default();
+    update_server(runner, socket, true, &mut report);
+    if persist {
+        update_config(true, &mut report);
+    }
+    report
+}
+
+/// Removes the hooks from the running tmux server and from tmux.conf
+pub fn uninstall(runner: &dyn CommandRunner, socket: Option<&str>) -> HooksReport {
+    let mut report = HooksReport::default();
+    update_server(runner, socket, false, &mut report);
+    update_config(false, &mut report);
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::CommandOutput;
+
+    /// Answers every command with the same output
+    struct FixedRunner(CommandOutput);
+
+    impl CommandRunner for FixedRunner {
+        fn run(&self, _program: &str, _args: &[&str]) -> CommandOutput {
+            self.0.clone()
+        }
+    }
+
+    fn hook_payload(hook: &str) -> NotifyPayload {
+        let command = hook_command(hook);
+        // Undo the tmux quoting to get the JSON curl sends
+        let start = command.find("-d '").unwrap() + "-d '".len();
+        let json = &command[start..=command.rfind('}').unwrap()];
+        serde_json::from_str(&json.replace("\\\"", "\"")).unwrap()
+    }
+
+    #[test]
+    fn hook_command_only_expands_safe_formats() {
+        for hook in HOOKS {
+            let command = hook_command(hook);
+            let formats: Vec<&str> = command
+                .match_indices("#{")
+                .map(|(i, _)| &command[i..])
+                .collect();
+            assert!(formats
+                .iter()
+                .all(|f| f.starts_with("#{pane_id}") || f.starts_with("#{q:socket_path}")));
+            // The escaped socket path is a shell word of its own
+            assert!(command.contains(" -H x-lovnotifier-tmux-socket:#{q:socket_path} -d '{"));
+            let payload = hook_payload(hook);
+            assert_eq!(payload.tmux_hook.as_deref(), Some(hook));
+            assert_eq!(payload.tmux_pane_id.as_deref(), Some("#{pane_id}"));
+            assert_eq!(payload.tmux_socket, None);
+        }
+    }
+
+    #[test]
+    fn complete_payload_names_the_pane() {
+        let runner = FixedRunner(CommandOutput {
+            success: true,
+            code: Some(0),
+            stdout: "Bob's \"api\"\t3\tbuild\t1\t2\n".to_string(),
+            stderr: String::new(),
+        });
+        let mut payload = hook_payload("pane-died");
+        complete_payload(&runner, &mut payload);
+        assert_eq!(payload.title, "Pane exited (2) in Bob's \"api\":build");
+        assert_eq!(payload.tmux_session.as_deref(), Some("Bob's \"api\""));
+        assert_eq!(payload.tmux_window.as_deref(), Some("3"));
+        assert_eq!(payload.tmux_pane.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn complete_payload_keeps_the_generic_title_without_tmux() {
+        let runner = FixedRunner(CommandOutput {
+            stderr: "can't find pane: %7".to_string(),
+            ..CommandOutput::default()
+        });
+        let mut payload = hook_payload("alert-bell");
+        complete_payload(&runner, &mut payload);
+        assert_eq!(payload.title, "Bell in tmux");
+        assert_eq!(payload.tmux_session, None);
+    }
+}
@@ -0,0 +1,551 @@
+use crate::terminal::{
+    is_server_unreachable, run_tmux_with_timeout, CommandRunner, MuxKind, MuxTarget, SystemRunner,
+    TMUX_QUERY_TIMEOUT,
+};
+use crate::{Outcome, ReviewItem};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// `list-panes -a` walks every pane on the server, so it runs less often
+/// than the client poll
+const STALE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+const PANE_FORMAT: &str =
+    "#{session_name}\t#{window_index}\t#{window_name}\t#{pane_index}\t#{pane_id}";
+
+/// How long a pane Lovnotifier switched to is not counted as the user's own
+/// visit, at least two polls
+const NAVIGATION_GRACE: Duration = Duration::from_secs(10);
+
+/// Panes Lovnotifier itself navigated to and when, until the watcher sees
+/// them shown or they expire
+static NAVIGATED: LazyLock<Mutex<Vec<(MuxTarget, Instant)>>> =
+    LazyLock::new(|| Mutex::new(Vec::new()));
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct TmuxWatchSettings {
+    /// Complete pending items once their pane is shown in an attached client
+    pub auto_complete: bool,
+    pub interval_ms: u64,
+    /// Flag pending items whose tmux pane no longer exists
+    pub detect_stale: bool,
+    /// Archive stale items after this many seconds (unset = keep them)
+    pub stale_archive_after_secs: Option<u64>,
+}
+
+impl Default for TmuxWatchSettings {
+    fn default() -> Self {
+        Self {
+            auto_complete: true,
+            interval_ms: 1500,
+            detect_stale: true,
+            stale_archive_after_secs: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TmuxPane {
+    /// Server the pane lives on, as given in `ReviewItem::tmux_socket`
+    pub socket: Option<String>,
+    pub session: String,
+    pub window_index: String,
+    pub window_name: String,
+    pub pane_index: String,
+    pub pane_id: String,
+}
+
+impl TmuxPane {
+    /// A pane id identifies the pane regardless of renumbering. Otherwise
+    /// items carry whatever the sender put in `tmux_window`, which is usually
+    /// the index but may be the window name.
+    pub fn matches(&self, item: &ReviewItem) -> bool {
+        item.mux_target()
+            .is_some_and(|target| self.matches_target(&target))
+    }
+
+    /// Same as `matches`, for a navigation target. An empty window or pane
+    /// matches any.
+    pub fn matches_target(&self, target: &MuxTarget) -> bool {
+        if target.kind != MuxKind::Tmux
+            || target.socket != self.socket
+            || target.ssh_target.is_some()
+        {
+            return false;
+        }
+        if let Some(pane_id) = &target.pane_id {
+            return *pane_id == self.pane_id;
+        }
+        let window_matches = target.window.is_empty()
+            || target.window == self.window_index
+            || target.window == self.window_name;
+        let pane_matches = target.pane.is_empty() || target.pane == self.pane_index;
+        !target.session.is_empty()
+            && target.session == self.session
+            && window_matches
+            && pane_matches
+    }
+}
+
+/// Records that Lovnotifier is about to show `target`, so the watcher does
+/// not complete its items as seen by the user. Remote and non-tmux targets
+/// are never watched.
+pub fn note_navigation(target: &MuxTarget) {
+    if target.kind == MuxKind::Tmux && target.ssh_target.is_none() {
+        NAVIGATED
+            .lock()
+            .unwrap()
+            .push((target.clone(), Instant::now()));
+    }
+}
+
+fn parse_panes(socket: Option<&str>, stdout: &str) -> HashSet<TmuxPane> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            Some(TmuxPane {
+                socket: socket.map(str::to_string),
+                session: fields.next()?.to_string(),
+                window_index: fields.next()?.to_string(),
+                window_name: fields.next()?.to_string(),
+                pane_index: fields.next()?.to_string(),
+                pane_id: fields.next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// The default server plus every server a pending item points at. A server
+/// joins on its first item, whose pane may well be the one on screen, which
+/// is why `ActivePanes` never completes anything on a server's first poll.
+fn watched_sockets(queue: &[ReviewItem]) -> Vec<Option<String>> {
+    let mut sockets = vec![None];
+    for item in queue
+        .iter()
+        .filter(|item| item.mux_kind == MuxKind::Tmux && item.remote_target().is_none())
+    {
+        if !sockets.contains(&item.tmux_socket) {
+            sockets.push(item.tmux_socket.clone());
+        }
+    }
+    sockets
+}
+
+/// Lists the pane each client attached to `socket` is showing, or `None`
+/// when that tmux server is not reachable
+pub fn active_panes(runner: &dyn CommandRunner, socket: Option<&str>) -> Option<HashSet<TmuxPane>> {
+    let output = run_tmux_with_timeout(
+        runner,
+        socket,
+        &["list-clients", "-F", PANE_FORMAT],
+        TMUX_QUERY_TIMEOUT,
+    );
+    output.success.then(|| parse_panes(socket, &output.stdout))
+}
+
+/// Lists every pane on the server. A server that is not running has no
+/// panes; `None` means tmux could not be asked at all (e.g. not installed or
+/// hung), in which case nothing should be declared stale.
+pub fn all_panes(runner: &dyn CommandRunner, socket: Option<&str>) -> Option<HashSet<TmuxPane>> {
+    let output = run_tmux_with_timeout(
+        runner,
+        socket,
+        &["list-panes", "-a", "-F", PANE_FORMAT],
+        TMUX_QUERY_TIMEOUT,
+    );
+    if output.success {
+        Some(parse_panes(socket, &output.stdout))
+    } else if is_server_unreachable(&output.stderr) {
+        Some(HashSet::new())
+    } else {
+        None
+    }
+}
+
+/// Ids of pending items whose pane just became visible. Only panes that were
+/// not showing on the previous poll count, so an item raised in the pane the
+/// user is already looking at stays until they actually switch to it.
+pub fn newly_seen_items(
+    queue: &[ReviewItem],
+    previous: &HashSet<TmuxPane>,
+    current: &HashSet<TmuxPane>,
+) -> Vec<String> {
+    let newly_active: Vec<&TmuxPane> = current.difference(previous).collect();
+    if newly_active.is_empty() {
+        return Vec::new();
+    }
+    queue
+        .iter()
+        .filter(|item| newly_active.iter().any(|pane| pane.matches(item)))
+        .map(|item| item.id.clone())
+        .collect()
+}
+
+/// The panes attached clients showed at the last poll, per tmux server. A
+/// server is only compared once it has a baseline from an earlier successful
+/// poll, so whatever is on screen when the app starts or a server comes back
+/// is recorded rather than counted as newly seen.
+#[derive(Default)]
+pub struct ActivePanes {
+    by_socket: HashMap<Option<String>, HashSet<TmuxPane>>,
+}
+
+impl ActivePanes {
+    /// Records what `socket` shows now, `None` when it was unreachable, and
+    /// returns the ids of items whose pane became visible since the last poll.
+    /// A pane that matches one of `navigated` was switched to by Lovnotifier
+    /// rather than the user; it joins the baseline without completing
+    /// anything, and the navigation it matched is used up.
+    pub fn update(
+        &mut self,
+        queue: &[ReviewItem],
+        socket: Option<String>,
+        current: Option<HashSet<TmuxPane>>,
+        navigated: &mut Vec<(MuxTarget, Instant)>,
+    ) -> Vec<String> {
+        let Some(current) = current else {
+            self.by_socket.remove(&socket);
+            return Vec::new();
+        };
+        let seen = match self.by_socket.get(&socket) {
+            Some(previous) => {
+                let mut baseline = previous.clone();
+                for pane in current.difference(previous) {
+                    if let Some(index) = navigated
+                        .iter()
+                        .position(|(target, _)| pane.matches_target(target))
+                    {
+                        navigated.remove(index);
+                        baseline.insert(pane.clone());
+                    }
+                }
+                newly_seen_items(queue, &baseline, &current)
+            }
+            None => Vec::new(),
+        };
+        self.by_socket.insert(socket, current);
+        seen
+    }
+
+    /// Forgets servers that are no longer polled. Their baseline would be
+    /// out of date by the time an item for them arrives again.
+    pub fn retain(&mut self, sockets: &[Option<String>]) {
+        self.by_socket.retain(|socket, _| sockets.contains(socket));
+    }
+}
+
+/// Sets or clears `stale_since` on items with tmux coordinates depending on
+/// whether their pane still exists. Items on servers outside `checked` are
+/// left alone. Returns whether any item changed.
+pub fn mark_stale_items(
+    queue: &mut [ReviewItem],
+    panes: &HashSet<TmuxPane>,
+    checked: &[Option<String>],
+    now: u64,
+) -> bool {
+    let mut changed = false;
+    for item in queue.iter_mut().filter(|item| {
+        item.mux_kind == MuxKind::Tmux
+            && (item.tmux_session.is_some() || item.tmux_pane_id.is_some())
+            && item.remote_target().is_none()
+            && checked.contains(&item.tmux_socket)
+    }) {
+        let exists = panes.iter().any(|pane| pane.matches(item));
+        match (exists, item.stale_since) {
+            (false, None) => {
+                item.stale_since = Some(now);
+                changed = true;
+            }
+            (true, Some(_)) => {
+                item.stale_since = None;
+                changed = true;
+            }
+            _ => {}
+        }
+    }
+    changed
+}
+
+/// Ids of items that have been stale for longer than `grace_secs`
+pub fn expired_stale_items(queue: &[ReviewItem], grace_secs: u64, now: u64) -> Vec<String> {
+    queue
+        .iter()
+        .filter(|item| {
+            item.stale_since
+                .is_some_and(|since| since + grace_secs <= now)
+        })
+        .map(|item| item.id.clone())
+        .collect()
+}
+
+fn check_stale(app: &tauri::AppHandle, settings: &TmuxWatchSettings) {
+    let sockets = watched_sockets(&crate::REVIEW_QUEUE.lock().unwrap());
+    let mut panes = HashSet::new();
+    let mut checked = Vec::new();
+    for socket in sockets {
+        if let Some(found) = all_panes(&SystemRunner, socket.as_deref()) {
+            panes.extend(found);
+            checked.push(socket);
+        }
+    }
+    if checked.is_empty() {
+        return;
+    }
+    let now = crate::unix_now().as_secs();
+
+    let (changed, expired) = {
+        let mut queue = crate::REVIEW_QUEUE.lock().unwrap();
+        let changed = mark_stale_items(&mut queue, &panes, &checked, now);
+        let expired = settings
+            .stale_archive_after_secs
+            .map(|grace| expired_stale_items(&queue, grace, now))
+            .unwrap_or_default();
+        (changed, expired)
+    };
+
+    if !expired.is_empty() {
+        println!(
+            "[Lovnotifier] Archiving {} stale review items",
+            expired.len()
+        );
+        crate::complete_review_items(app, &expired, Outcome::Stale);
+    } else if changed {
+        crate::publish_review_queue(app);
+    }
+}
+
+/// Polls tmux in a background thread. Completes items the user reached on
+/// their own, e.g. with tmux key bindings, and flags items whose pane is gone.
+pub fn start(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        let mut active = ActivePanes::default();
+        let mut last_stale_check: Option<Instant> = None;
+        loop {
+            let settings = crate::current_settings().tmux_watch;
+            if settings.detect_stale
+                && last_stale_check.is_none_or(|at| at.elapsed() >= STALE_CHECK_INTERVAL)
+            {
+                check_stale(&app, &settings);
+                last_stale_check = Some(Instant::now());
+            }
+            let grace = NAVIGATION_GRACE.max(Duration::from_millis(settings.interval_ms * 2));
+            NAVIGATED
+                .lock()
+                .unwrap()
+                .retain(|(_, at)| at.elapsed() < grace);
+            if settings.auto_complete {
+                let sockets = watched_sockets(&crate::REVIEW_QUEUE.lock().unwrap());
+                active.retain(&sockets);
+                let mut seen = Vec::new();
+                for socket in sockets {
+                    let current = active_panes(&SystemRunner, socket.as_deref());
+                    let queue = crate::REVIEW_QUEUE.lock().unwrap();
+                    let mut navigated = NAVIGATED.lock().unwrap();
+                    seen.extend(active.update(&queue, socket, current, &mut navigated));
+                }
+                if !seen.is_empty() {
+                    println!("[Lovnotifier] {} items seen in terminal", seen.len());
+                    crate::complete_review_items(&app, &seen, Outcome::SeenInTerminal);
+                }
+            } else {
+                // Start from a fresh baseline when turned back on
+                active = ActivePanes::default();
+            }
+            std::thread::sleep(Duration::from_millis(settings.interval_ms.max(250)));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal::CommandOutput;
+
+    fn item(id: &str, pane_id: &str) -> ReviewItem {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "seq": 1,
+            "title": id,
+            "timestamp": 0,
+            "tmux_session": "work",
+            "tmux_pane_id": pane_id,
+        }))
+        .unwrap()
+    }
+
+    fn panes(pane_ids: &[&str]) -> Option<HashSet<TmuxPane>> {
+        panes_on(None, pane_ids)
+    }
+
+    fn panes_on(socket: Option<&str>, pane_ids: &[&str]) -> Option<HashSet<TmuxPane>> {
+        Some(
+            pane_ids
+                .iter()
+                .map(|pane_id| TmuxPane {
+                    socket: socket.map(str::to_string),
+                    session: "work".to_string(),
+                    window_index: "1".to_string(),
+                    window_name: "zsh".to_string(),
+                    pane_index: "0".to_string(),
+                    pane_id: pane_id.to_string(),
+                })
+                .collect(),
+        )
+    }
+
+    /// A tmux server that never answers
+    struct HungRunner;
+
+    impl CommandRunner for HungRunner {
+        fn run(&self, _program: &str, _args: &[&str]) -> CommandOutput {
+            panic!("the watcher must not wait for tmux without a timeout")
+        }
+
+        fn run_with_timeout(
+            &self,
+            program: &str,
+            _args: &[&str],
+            timeout: Duration,
+        ) -> CommandOutput {
+            CommandOutput {
+                stderr: format!("{} timed out after {:?}", program, timeout),
+                ..CommandOutput::default()
+            }
+        }
+    }
+
+    #[test]
+    fn hung_server_times_out_without_declaring_panes_gone() {
+        assert_eq!(
+            active_panes(&HungRunner, Some("/tmp/tmux-1000/stale")),
+            None
+        );
+        assert_eq!(all_panes(&HungRunner, Some("/tmp/tmux-1000/stale")), None);
+    }
+
+    #[test]
+    fn first_poll_only_records_a_baseline() {
+        let queue = [item("a", "%1"), item("b", "%2")];
+        let mut active = ActivePanes::default();
+        assert!(active
+            .update(&queue, None, panes(&["%1"]), &mut Vec::new())
+            .is_empty());
+        assert!(active
+            .update(&queue, None, panes(&["%1"]), &mut Vec::new())
+            .is_empty());
+        assert_eq!(
+            active.update(&queue, None, panes(&["%2"]), &mut Vec::new()),
+            ["b"]
+        );
+    }
+
+    #[test]
+    fn unreachable_server_resets_its_baseline() {
+        let queue = [item("a", "%1")];
+        let mut active = ActivePanes::default();
+        active.update(&queue, None, panes(&[]), &mut Vec::new());
+        assert!(active
+            .update(&queue, None, None, &mut Vec::new())
+            .is_empty());
+        assert!(active
+            .update(&queue, None, panes(&["%1"]), &mut Vec::new())
+            .is_empty());
+    }
+
+    #[test]
+    fn first_item_on_a_socket_is_not_completed() {
+        let socket = Some("ci".to_string());
+        let mut raised = item("a", "%5");
+        raised.tmux_socket = socket.clone();
+        let queue = [raised];
+        let mut active = ActivePanes::default();
+        active.update(&[], None, panes(&[]), &mut Vec::new());
+
+        // The item arrives from the pane the user is looking at
+        let sockets = watched_sockets(&queue);
+        assert_eq!(sockets, [None, socket.clone()]);
+        active.retain(&sockets);
+        let seen = active.update(
+            &queue,
+            socket.clone(),
+            panes_on(Some("ci"), &["%5"]),
+            &mut Vec::new(),
+        );
+        assert!(seen.is_empty());
+        let seen = active.update(
+            &queue,
+            socket.clone(),
+            panes_on(Some("ci"), &["%5"]),
+            &mut Vec::new(),
+        );
+        assert!(seen.is_empty());
+    }
+
+    #[test]
+    fn socket_baseline_is_dropped_while_unwatched() {
+        let socket = Some("ci".to_string());
+        let mut raised = item("a", "%5");
+        raised.tmux_socket = socket.clone();
+        let queue = [raised];
+        let mut active = ActivePanes::default();
+        active.update(
+            &queue,
+            socket.clone(),
+            panes_on(Some("ci"), &["%4"]),
+            &mut Vec::new(),
+        );
+
+        active.retain(&[None]);
+        let seen = active.update(
+            &queue,
+            socket.clone(),
+            panes_on(Some("ci"), &["%5"]),
+            &mut Vec::new(),
+        );
+        assert!(seen.is_empty());
+    }
+
+    fn target(pane_id: &str) -> MuxTarget {
+        MuxTarget {
+            pane_id: Some(pane_id.to_string()),
+            session: "work".to_string(),
+            ..MuxTarget::default()
+        }
+    }
+
+    #[test]
+    fn navigated_pane_is_not_completed() {
+        let queue = [item("a", "%1"), item("b", "%2")];
+        let mut active = ActivePanes::default();
+        let mut navigated = vec![(target("%2"), Instant::now())];
+        active.update(&queue, None, panes(&["%1"]), &mut navigated);
+        assert!(active
+            .update(&queue, None, panes(&["%2"]), &mut navigated)
+            .is_empty());
+        assert!(navigated.is_empty());
+
+        // A later visit of the user's own still counts
+        active.update(&queue, None, panes(&["%1"]), &mut navigated);
+        assert_eq!(
+            active.update(&queue, None, panes(&["%2"]), &mut navigated),
+            ["b"]
+        );
+    }
+
+    #[test]
+    fn navigation_elsewhere_is_kept() {
+        let queue = [item("a", "%1"), item("b", "%2")];
+        let mut active = ActivePanes::default();
+        let mut navigated = vec![(target("%3"), Instant::now())];
+        active.update(&queue, None, panes(&["%1"]), &mut navigated);
+        assert_eq!(
+            active.update(&queue, None, panes(&["%2"]), &mut navigated),
+            ["b"]
+        );
+        assert_eq!(navigated.len(), 1);
+    }
+}
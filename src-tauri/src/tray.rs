@@ -0,0 +1,460 @@
+use crate::focus::FocusSettings;
+use crate::{NotifierSettings, Outcome, ReviewItem, REVIEW_QUEUE};
+use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
+use std::sync::{LazyLock, Mutex};
+use tauri::menu::{
+    CheckMenuItem, CheckMenuItemBuilder, Menu, MenuBuilder, MenuItemBuilder, Submenu,
+    SubmenuBuilder,
+};
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_opener::OpenerExt;
+
+const TITLE_LEN: usize = 40;
+
+/// Hash of what the tray showed when it was last built
+static LAST_MENU_STATE: LazyLock<Mutex<Option<u64>>> = LazyLock::new(|| Mutex::new(None));
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayGrouping {
+    /// One flat list of all items
+    None,
+    #[default]
+    Project,
+    /// tmux session, qualified by host for remote items
+    Session,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct TraySettings {
+    pub group_by: TrayGrouping,
+    /// Durations offered by each item's Snooze entries
+    pub snooze_minutes: Vec<u64>,
+}
+
+impl Default for TraySettings {
+    fn default() -> Self {
+        Self {
+            group_by: TrayGrouping::default(),
+            snooze_minutes: vec![15, 60],
+        }
+    }
+}
+
+/// What an entry in an item's submenu does. Entry ids have the form
+/// `msg:<action>:<item id>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemAction {
+    /// Navigates to the item and completes it
+    Open,
+    /// Navigates to the item and keeps it pending
+    GoTo,
+    /// Completes the item without navigating
+    Dismiss,
+    Snooze {
+        minutes: u64,
+    },
+    CopyTitle,
+    CopyDetails,
+    OpenLink,
+    OpenProject,
+}
+
+impl ItemAction {
+    fn key(self) -> String {
+        match self {
+            ItemAction::Open => "open".to_string(),
+            ItemAction::GoTo => "goto".to_string(),
+            ItemAction::Dismiss => "dismiss".to_string(),
+            ItemAction::Snooze { minutes } => format!("snooze{}", minutes),
+            ItemAction::CopyTitle => "copy_title".to_string(),
+            ItemAction::CopyDetails => "copy_details".to_string(),
+            ItemAction::OpenLink => "open_link".to_string(),
+            ItemAction::OpenProject => "open_project".to_string(),
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Some(match key {
+            "open" => ItemAction::Open,
+            "goto" => ItemAction::GoTo,
+            "dismiss" => ItemAction::Dismiss,
+            "copy_title" => ItemAction::CopyTitle,
+            "copy_details" => ItemAction::CopyDetails,
+            "open_link" => ItemAction::OpenLink,
+            "open_project" => ItemAction::OpenProject,
+            _ => ItemAction::Snooze {
+                minutes: key.strip_prefix("snooze")?.parse().ok()?,
+            },
+        })
+    }
+
+    fn id(self, item: &ReviewItem) -> String {
+        format!("msg:{}:{}", self.key(), item.id)
+    }
+}
+
+/// Splits a menu event id into the item action and the item id
+pub fn parse_item_event(id: &str) -> Option<(ItemAction, &str)> {
+    let (key, item_id) = id.strip_prefix("msg:")?.split_once(':')?;
+    Some((ItemAction::from_key(key)?, item_id))
+}
+
+fn truncate_str(s: &str, max_len: usize) -> String {
+    if s.chars().count() <= max_len {
+        s.to_string()
+    } else {
+        let truncated: String = s.chars().take(max_len - 1).collect();
+        format!("{}…", truncated)
+    }
+}
+
+fn group_key(item: &ReviewItem, grouping: TrayGrouping) -> String {
+    match grouping {
+        TrayGrouping::None => String::new(),
+        TrayGrouping::Project => item.project.clone().unwrap_or_else(|| "Other".to_string()),
+        // Only sessions on another machine are qualified; a `host` naming
+        // this one is the same tmux server as an item without it
+        TrayGrouping::Session => match (&item.tmux_session, item.remote_target()) {
+            (Some(session), Some(remote)) => {
+                let host = item.host.clone().filter(|h| !h.is_empty());
+                format!("{} on {}", session, host.unwrap_or(remote))
+            }
+            (Some(session), None) => session.clone(),
+            (None, _) => "No session".to_string(),
+        },
+    }
+}
+
+fn snooze_label(minutes: u64) -> String {
+    match minutes {
+        60 => "Snooze 1 Hour".to_string(),
+        m if m > 60 && m % 60 == 0 => format!("Snooze {} Hours", m / 60),
+        1 => "Snooze 1 Minute".to_string(),
+        m => format!("Snooze {} Minutes", m),
+    }
+}
+
+fn build_item<R: Runtime>(
+    app: &AppHandle<R>,
+    item: &ReviewItem,
+    snooze_minutes: &[u64],
+) -> tauri::Result<Submenu<R>> {
+    let marker = if item.stale_since.is_some() {
+        "⊘ "
+    } else if item.nav_error.is_some() {
+        "⚠ "
+    } else {
+        ""
+    };
+    let label = format!(
+        "{}#{} {}",
+        marker,
+        item.seq,
+        truncate_str(&item.title, TITLE_LEN)
+    );
+    let entry = |action: ItemAction, text: String, enabled: bool| {
+        MenuItemBuilder::with_id(action.id(item), text)
+            .enabled(enabled)
+            .build(app)
+    };
+    // Stale panes are gone and remote project paths are not on this machine
+    let can_navigate = item.mux_target().is_some() && item.stale_since.is_none();
+    let has_local_project = item.project_path.is_some() && item.remote_target().is_none();
+
+    let mut submenu = SubmenuBuilder::new(app, label)
+        .item(&entry(ItemAction::Open, "Open".to_string(), true)?)
+        .item(&entry(
+            ItemAction::GoTo,
+            "Go to Pane".to_string(),
+            can_navigate,
+        )?)
+        .item(&entry(ItemAction::Dismiss, "Dismiss".to_string(), true)?)
+        .separator();
+    for &minutes in snooze_minutes.iter().filter(|&&m| m > 0) {
+        submenu = submenu.item(&entry(
+            ItemAction::Snooze { minutes },
+            snooze_label(minutes),
+            true,
+        )?);
+    }
+    submenu
+        .separator()
+        .item(&entry(
+            ItemAction::CopyTitle,
+            "Copy Title".to_string(),
+            true,
+        )?)
+        .item(&entry(
+            ItemAction::CopyDetails,
+            "Copy Details".to_string(),
+            true,
+        )?)
+        .item(&entry(
+            ItemAction::OpenLink,
+            "Open Link".to_string(),
+            item.link.is_some(),
+        )?)
+        .item(&entry(
+            ItemAction::OpenProject,
+            "Open Project Folder".to_string(),
+            has_local_project,
+        )?)
+        .build()
+}
+
+/// Runs an entry from an item's submenu. Items that left the queue since the
+/// menu was built are ignored.
+pub fn run_item_action<R: Runtime>(app: &AppHandle<R>, action: ItemAction, item_id: &str) {
+    let item = {
+        let queue = REVIEW_QUEUE.lock().unwrap();
+        queue.iter().find(|item| item.id == item_id).cloned()
+    };
+    let Some(item) = item else {
+        return;
+    };
+
+    let result = match action {
+        ItemAction::Open => {
+            crate::consume_review_item(app, &item.id);
+            Ok(())
+        }
+        ItemAction::GoTo => {
+            let _ = app.emit("review-item-selected", &item.id);
+            let (app, item) = (app.clone(), item.clone());
+            tauri::async_runtime::spawn_blocking(move || {
+                let _ = crate::navigate_review_item(&app, &item);
+            });
+            Ok(())
+        }
+        ItemAction::Dismiss => {
+            crate::complete_review_items(app, std::slice::from_ref(&item.id), Outcome::Dismissed);
+            Ok(())
+        }
+        ItemAction::Snooze { minutes } => {
+            crate::snooze_review_item(app, &item.id, minutes * 60);
+            Ok(())
+        }
+        ItemAction::CopyTitle => app
+            .clipboard()
+            .write_text(item.title.as_str())
+            .map_err(|e| e.to_string()),
+        // Items carry no body of their own; this is the text notifications show
+        ItemAction::CopyDetails => app
+            .clipboard()
+            .write_text(crate::notifications::body(&item))
+            .map_err(|e| e.to_string()),
+        ItemAction::OpenLink => match &item.link {
+            Some(link) => app
+                .opener()
+                .open_url(link, None::<&str>)
+                .map_err(|e| e.to_string()),
+            None => Ok(()),
+        },
+        ItemAction::OpenProject => match &item.project_path {
+            Some(path) => app
+                .opener()
+                .open_path(path, None::<&str>)
+                .map_err(|e| e.to_string()),
+            None => Ok(()),
+        },
+    };
+    if let Err(e) = result {
+        println!(
+            "[Lovnotifier] Tray action {:?} on #{} failed: {}",
+            action, item.seq, e
+        );
+    }
+}
+
+/// Check entries for the known projects or sessions: those focused on plus
+/// those of pending items
+fn focus_entries<R: Runtime>(
+    app: &AppHandle<R>,
+    kind: &str,
+    focused: &[String],
+    pending: impl Iterator<Item = String>,
+) -> tauri::Result<Vec<CheckMenuItem<R>>> {
+    let mut names: Vec<String> = focused.iter().cloned().chain(pending).collect();
+    names.sort();
+    names.dedup();
+    names
+        .iter()
+        .map(|name| {
+            CheckMenuItemBuilder::with_id(
+                format!("focus:{}:{}", kind, name),
+                truncate_str(name, TITLE_LEN),
+            )
+            .checked(focused.contains(name))
+            .build(app)
+        })
+        .collect()
+}
+
+fn build_focus_menu<R: Runtime>(
+    app: &AppHandle<R>,
+    focus: &FocusSettings,
+    queue: &[ReviewItem],
+) -> tauri::Result<Submenu<R>> {
+    let label = if focus.is_filtering() {
+        let names: Vec<&str> = focus
+            .projects
+            .iter()
+            .chain(&focus.sessions)
+            .map(String::as_str)
+            .collect();
+        format!("Focus: {}", truncate_str(&names.join(", "), TITLE_LEN))
+    } else {
+        "Focus".to_string()
+    };
+    let toggle = CheckMenuItemBuilder::with_id("focus:toggle", "Focus Mode")
+        .checked(focus.enabled)
+        .build(app)?;
+    let mut submenu = SubmenuBuilder::new(app, label).item(&toggle);
+
+    let projects = focus_entries(
+        app,
+        "project",
+        &focus.projects,
+        queue.iter().filter_map(|item| item.project.clone()),
+    )?;
+    let sessions = focus_entries(
+        app,
+        "session",
+        &focus.sessions,
+        queue.iter().filter_map(|item| item.tmux_session.clone()),
+    )?;
+    for (heading, entries) in [("Projects", projects), ("tmux Sessions", sessions)] {
+        if entries.is_empty() {
+            continue;
+        }
+        let heading = MenuItemBuilder::new(heading).enabled(false).build(app)?;
+        submenu = submenu.separator().item(&heading);
+        for entry in &entries {
+            submenu = submenu.item(entry);
+        }
+    }
+    submenu.build()
+}
+
+/// Hashes what the tray shows for `queue` and `settings`
+fn menu_state(queue: &[ReviewItem], settings: &NotifierSettings) -> u64 {
+    let state = serde_json::to_string(&(
+        queue,
+        &settings.focus,
+        &settings.tray,
+        crate::dnd::is_active(&settings.dnd),
+        crate::dnd::tooltip(&settings.dnd),
+    ))
+    .unwrap_or_default();
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    state.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Records the tray for `queue` and `settings` as built, e.g. when the tray
+/// is first created from them
+pub fn remember_menu(queue: &[ReviewItem], settings: &NotifierSettings) {
+    *LAST_MENU_STATE.lock().unwrap() = Some(menu_state(queue, settings));
+}
+
+/// Whether the tray for `queue` and `settings` differs from the one last
+/// built; records it as built. Rebuilding means a dozen native menu items per
+/// pending item, so polls that changed nothing should not do it.
+pub fn menu_changed(queue: &[ReviewItem], settings: &NotifierSettings) -> bool {
+    let hash = menu_state(queue, settings);
+    LAST_MENU_STATE.lock().unwrap().replace(hash) != Some(hash)
+}
+
+/// Lists every pending item in focus, oldest first. Items are grouped into
+/// submenus labelled with their counts, unless grouping is off or all items
+/// share a group.
+///
+/// Takes a copy of the queue: creating menu items off the main thread waits
+/// for the main thread, which may itself be waiting for `REVIEW_QUEUE`.
+pub fn build_menu<R: Runtime>(
+    app: &AppHandle<R>,
+    queue: &[ReviewItem],
+    settings: &NotifierSettings,
+) -> tauri::Result<Menu<R>> {
+    let visible: Vec<&ReviewItem> = queue
+        .iter()
+        .filter(|item| settings.focus.matches(item))
+        .collect();
+    let hidden = queue.len() - visible.len();
+    let mut menu_builder = MenuBuilder::new(app);
+
+    if visible.is_empty() {
+        let label = if hidden > 0 {
+            "No messages in focus"
+        } else {
+            "No messages"
+        };
+        let empty_item = MenuItemBuilder::with_id("empty", label)
+            .enabled(false)
+            .build(app)?;
+        menu_builder = menu_builder.item(&empty_item);
+    } else {
+        let mut sorted = visible;
+        sorted.sort_by_key(|item| item.timestamp);
+
+        // Groups in order of their oldest item
+        let mut groups: Vec<(String, Vec<&ReviewItem>)> = Vec::new();
+        for item in sorted {
+            let key = group_key(item, settings.tray.group_by);
+            match groups.iter_mut().find(|(existing, _)| *existing == key) {
+                Some((_, items)) => items.push(item),
+                None => groups.push((key, vec![item])),
+            }
+        }
+
+        let snooze_minutes = &settings.tray.snooze_minutes;
+        if groups.len() == 1 {
+            for item in &groups[0].1 {
+                menu_builder = menu_builder.item(&build_item(app, item, snooze_minutes)?);
+            }
+        } else {
+            for (key, items) in &groups {
+                let mut submenu = SubmenuBuilder::new(
+                    app,
+                    format!("{} ({})", truncate_str(key, TITLE_LEN), items.len()),
+                );
+                for item in items {
+                    submenu = submenu.item(&build_item(app, item, snooze_minutes)?);
+                }
+                menu_builder = menu_builder.item(&submenu.build()?);
+            }
+        }
+    }
+
+    if hidden > 0 {
+        let hidden_item =
+            MenuItemBuilder::with_id("hidden", format!("{} more outside focus", hidden))
+                .enabled(false)
+                .build(app)?;
+        menu_builder = menu_builder.item(&hidden_item);
+    }
+
+    menu_builder = menu_builder.separator();
+
+    let focus_menu = build_focus_menu(app, &settings.focus, queue)?;
+    let dnd_item = CheckMenuItemBuilder::with_id("tray_dnd", "Do Not Disturb")
+        .checked(crate::dnd::is_active(&settings.dnd))
+        .build(app)?;
+    let toggle_float =
+        MenuItemBuilder::with_id("tray_toggle_float", "Toggle Float Window").build(app)?;
+    let settings_item = MenuItemBuilder::with_id("tray_settings", "Settings...").build(app)?;
+    let quit_item = MenuItemBuilder::with_id("tray_quit", "Quit").build(app)?;
+
+    menu_builder
+        .item(&focus_menu)
+        .item(&dnd_item)
+        .item(&toggle_float)
+        .item(&settings_item)
+        .separator()
+        .item(&quit_item)
+        .build()
+}
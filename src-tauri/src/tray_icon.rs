@@ -0,0 +1,163 @@
+use crate::{Priority, ReviewItem};
+use image::{Rgba, RgbaImage};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use tauri::image::Image;
+
+/// The embedded tray icon, a template image on macOS
+static BASE_ICON: LazyLock<Option<RgbaImage>> = LazyLock::new(|| {
+    image::load_from_memory(include_bytes!("../icons/tray-icon.png"))
+        .map(|img| img.to_rgba8())
+        .ok()
+});
+
+/// Badged icons by badge text and priority. Counts above 99 share one text,
+/// so this stays small.
+static BADGE_CACHE: LazyLock<Mutex<HashMap<(String, Priority), Image<'static>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+const GLYPH_WIDTH: u32 = 3;
+const GLYPH_HEIGHT: u32 = 5;
+
+/// 3x5 pixel glyph for the badge text, one row per entry, most significant
+/// bit on the left
+fn glyph(c: char) -> [u8; 5] {
+    match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        _ => [0; 5],
+    }
+}
+
+fn badge_color(priority: Priority) -> Rgba<u8> {
+    match priority {
+        Priority::Low => Rgba([0x8e, 0x8e, 0x93, 0xff]),
+        Priority::Normal => Rgba([0x0a, 0x84, 0xff, 0xff]),
+        Priority::High => Rgba([0xff, 0x9f, 0x0a, 0xff]),
+        Priority::Urgent => Rgba([0xff, 0x3b, 0x30, 0xff]),
+    }
+}
+
+fn to_image(rgba: RgbaImage) -> Image<'static> {
+    let (width, height) = rgba.dimensions();
+    Image::new_owned(rgba.into_raw(), width, height)
+}
+
+/// Draws `color` over `pixel` with the given coverage
+fn blend(pixel: &mut Rgba<u8>, color: Rgba<u8>, coverage: f32) {
+    let src = coverage * color[3] as f32 / 255.0;
+    let dst = pixel[3] as f32 / 255.0;
+    let alpha = src + dst * (1.0 - src);
+    if alpha <= 0.0 {
+        return;
+    }
+    for channel in 0..3 {
+        let value =
+            (color[channel] as f32 * src + pixel[channel] as f32 * dst * (1.0 - src)) / alpha;
+        pixel[channel] = value.round() as u8;
+    }
+    pixel[3] = (alpha * 255.0).round() as u8;
+}
+
+/// Composites a pill-shaped badge with `text` onto the top right corner of
+/// `base`. A transparent ring separates the badge from the glyph below.
+fn render_badge(base: &RgbaImage, text: &str, color: Rgba<u8>) -> RgbaImage {
+    let mut icon = base.clone();
+    let size = icon.width().min(icon.height());
+    let chars = text.chars().count() as u32;
+    let text_width = |scale: u32| chars * (GLYPH_WIDTH + 1) * scale - scale;
+
+    let height = (size as f32 * 0.55).round() as u32;
+    let padding = height / 4;
+    let mut scale = ((height as f32 * 0.65) / GLYPH_HEIGHT as f32)
+        .round()
+        .max(1.0) as u32;
+    while scale > 1 && text_width(scale) + 2 * padding > icon.width() {
+        scale -= 1;
+    }
+    let width = (text_width(scale) + 2 * padding).clamp(height, icon.width());
+    let left = icon.width() - width;
+
+    // The pill is the set of points within `radius` of its centre line
+    let radius = height as f32 / 2.0;
+    let (start, end) = (left as f32 + radius, (left + width) as f32 - radius);
+    let ring = (size as f32 / 22.0).max(1.0);
+    for (x, y, pixel) in icon.enumerate_pixels_mut() {
+        let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+        let dx = px - px.clamp(start, end);
+        let dy = py - radius;
+        let distance = (dx * dx + dy * dy).sqrt();
+
+        let knockout = (radius + ring - distance + 0.5).clamp(0.0, 1.0);
+        pixel[3] = (pixel[3] as f32 * (1.0 - knockout)).round() as u8;
+        let coverage = (radius - distance + 0.5).clamp(0.0, 1.0);
+        if coverage > 0.0 {
+            blend(pixel, color, coverage);
+        }
+    }
+
+    let white = Rgba([0xff, 0xff, 0xff, 0xff]);
+    let text_left = left + (width - text_width(scale)) / 2;
+    let text_top = (height - GLYPH_HEIGHT * scale) / 2;
+    for (index, c) in text.chars().enumerate() {
+        let glyph_left = text_left + index as u32 * (GLYPH_WIDTH + 1) * scale;
+        for (row, bits) in glyph(c).iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - column)) == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let x = glyph_left + column * scale + dx;
+                        let y = text_top + row as u32 * scale + dy;
+                        if x < icon.width() && y < icon.height() {
+                            icon.put_pixel(x, y, white);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    icon
+}
+
+/// The tray icon for the pending queue and whether it is a template image.
+/// An empty queue shows the plain template icon; otherwise a badge with the
+/// count is tinted by the highest pending priority. The macOS menu bar turns
+/// light or dark and only a template image follows it, so there the icon
+/// always stays plain and the count goes in the title (see `title_for`).
+pub fn icon_for(queue: &[ReviewItem]) -> Option<(Image<'static>, bool)> {
+    let base = BASE_ICON.as_ref()?;
+    let priority = queue.iter().map(|item| item.priority).max();
+    let Some(priority) = priority.filter(|_| !cfg!(target_os = "macos")) else {
+        return Some((to_image(base.clone()), true));
+    };
+    let text = if queue.len() > 99 {
+        "99+".to_string()
+    } else {
+        queue.len().to_string()
+    };
+
+    let mut cache = BADGE_CACHE.lock().unwrap();
+    let icon = cache
+        .entry((text, priority))
+        .or_insert_with_key(|(text, priority)| {
+            to_image(render_badge(base, text, badge_color(*priority)))
+        });
+    Some((icon.clone(), false))
+}
+
+/// The menu bar title: the pending count on macOS, nothing elsewhere, where
+/// many tray hosts do not show titles and the icon carries the count
+pub fn title_for(queue: &[ReviewItem]) -> Option<String> {
+    cfg!(target_os = "macos").then(|| queue.len().to_string())
+}
@@ -0,0 +1,453 @@
+use crate::terminal::MuxKind;
+use crate::{NotifyPayload, Priority};
+use serde_json::Value;
+use std::collections::HashMap;
+
+// ============================================================================
+// Field Helpers
+// ============================================================================
+
+fn str_at<'a>(value: &'a Value, pointer: &str) -> Option<&'a str> {
+    value.pointer(pointer).and_then(Value::as_str)
+}
+
+fn string_at(value: &Value, pointer: &str) -> Option<String> {
+    match value.pointer(pointer)? {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn payload(
+    title: String,
+    project: Option<String>,
+    link: Option<String>,
+    priority: Priority,
+) -> NotifyPayload {
+    NotifyPayload {
+        title,
+        project,
+        project_path: None,
+        session_id: None,
+        mux_kind: MuxKind::Tmux,
+        tmux_session: None,
+        tmux_window: None,
+        tmux_pane: None,
+        tmux_pane_id: None,
+        tmux_socket: None,
+        tmux_hook: None,
+        host: None,
+        ssh_target: None,
+        source: None,
+        link,
+        priority,
+        sound: None,
+        tags: Vec::new(),
+        ttl_secs: None,
+        queue: None,
+        snapshot: None,
+    }
+}
+
+// ============================================================================
+// GitHub / Gitea
+// ============================================================================
+
+/// Maps a GitHub webhook, or a Gitea one, which mirrors the GitHub schema
+/// except for reviews. Returns `None` for events and actions that are not worth a notification.
+pub fn from_github(event: &str, body: &Value) -> Option<NotifyPayload> {
+    let repo = string_at(body, "/repository/name");
+
+    match event {
+        "workflow_run" => {
+            if str_at(body, "/action") != Some("completed") {
+                return None;
+            }
+            let run = body.get("workflow_run")?;
+            let name = str_at(run, "/name").unwrap_or("Workflow");
+            let conclusion = str_at(run, "/conclusion").unwrap_or("completed");
+            let branch = str_at(run, "/head_branch").unwrap_or("");
+            let priority = match conclusion {
+                "failure" | "timed_out" | "startup_failure" => Priority::High,
+                "cancelled" | "skipped" | "neutral" => Priority::Low,
+                _ => Priority::Normal,
+            };
+            let title = if branch.is_empty() {
+                format!("{} {}", name, conclusion)
+            } else {
+                format!("{} {} on {}", name, conclusion, branch)
+            };
+            Some(payload(title, repo, string_at(run, "/html_url"), priority))
+        }
+        "pull_request_review" => {
+            if str_at(body, "/action") != Some("submitted") {
+                return None;
+            }
+            let reviewer = str_at(body, "/review/user/login").unwrap_or("Someone");
+            let state = str_at(body, "/review/state").unwrap_or("commented");
+            let link = string_at(body, "/review/html_url")
+                .or_else(|| string_at(body, "/pull_request/html_url"));
+            Some(review_payload(body, reviewer, state, link, repo))
+        }
+        // Gitea names review events after their outcome; the review itself
+        // only carries `type` and `content`, so the reviewer is the sender
+        "pull_request_approved" | "pull_request_rejected" | "pull_request_comment" => {
+            if str_at(body, "/action") != Some("reviewed") {
+                return None;
+            }
+            let reviewer = str_at(body, "/sender/login").unwrap_or("Someone");
+            let state = match event {
+                "pull_request_approved" => "approved",
+                "pull_request_rejected" => "changes_requested",
+                _ => "commented",
+            };
+            let link = string_at(body, "/pull_request/html_url");
+            Some(review_payload(body, reviewer, state, link, repo))
+        }
+        _ => None,
+    }
+}
+
+/// Title and priority for a submitted pull request review
+fn review_payload(
+    body: &Value,
+    reviewer: &str,
+    state: &str,
+    link: Option<String>,
+    repo: Option<String>,
+) -> NotifyPayload {
+    let number = string_at(body, "/pull_request/number").unwrap_or_default();
+    let pr_title = str_at(body, "/pull_request/title").unwrap_or("");
+    let (verb, priority) = match state.to_ascii_lowercase().as_str() {
+        "approved" => ("approved", Priority::Normal),
+        "changes_requested" => ("requested changes on", Priority::High),
+        _ => ("commented on", Priority::Low),
+    };
+    let title = format!("{} {} #{} {}", reviewer, verb, number, pr_title);
+    payload(title.trim_end().to_string(), repo, link, priority)
+}
+
+// ============================================================================
+// GitLab
+// ============================================================================
+
+/// Maps a GitLab webhook using its `object_kind` field
+pub fn from_gitlab(body: &Value) -> Option<NotifyPayload> {
+    let project = string_at(body, "/project/name");
+    let web_url = str_at(body, "/project/web_url").unwrap_or("");
+
+    match str_at(body, "/object_kind")? {
+        "pipeline" => {
+            let status = str_at(body, "/object_attributes/status")?;
+            let priority = match status {
+                "failed" => Priority::High,
+                "success" => Priority::Normal,
+                "canceled" | "skipped" => Priority::Low,
+                // Running, pending and created pipelines are not terminal
+                _ => return None,
+            };
+            let git_ref = str_at(body, "/object_attributes/ref").unwrap_or("");
+            let id = string_at(body, "/object_attributes/id");
+            let title = if git_ref.is_empty() {
+                format!("Pipeline {}", status)
+            } else {
+                format!("Pipeline {} on {}", status, git_ref)
+            };
+            let link = string_at(body, "/object_attributes/url").or_else(|| {
+                id.filter(|_| !web_url.is_empty())
+                    .map(|id| format!("{}/-/pipelines/{}", web_url, id))
+            });
+            Some(payload(title, project, link, priority))
+        }
+        "merge_request" => {
+            let action = str_at(body, "/object_attributes/action")?;
+            let user = str_at(body, "/user/username").unwrap_or("Someone");
+            let iid = string_at(body, "/object_attributes/iid").unwrap_or_default();
+            let mr_title = str_at(body, "/object_attributes/title").unwrap_or("");
+            let (verb, priority) = match action {
+                "approved" => ("approved", Priority::Normal),
+                "unapproved" => ("unapproved", Priority::High),
+                _ => return None,
+            };
+            let title = format!("{} {} !{} {}", user, verb, iid, mr_title);
+            Some(payload(
+                title.trim_end().to_string(),
+                project,
+                string_at(body, "/object_attributes/url"),
+                priority,
+            ))
+        }
+        "note" => {
+            if str_at(body, "/object_attributes/noteable_type") != Some("MergeRequest") {
+                return None;
+            }
+            let user = str_at(body, "/user/username").unwrap_or("Someone");
+            let iid = string_at(body, "/merge_request/iid").unwrap_or_default();
+            let mr_title = str_at(body, "/merge_request/title").unwrap_or("");
+            let title = format!("{} commented on !{} {}", user, iid, mr_title);
+            Some(payload(
+                title.trim_end().to_string(),
+                project,
+                string_at(body, "/object_attributes/url"),
+                Priority::Low,
+            ))
+        }
+        _ => None,
+    }
+}
+
+// ============================================================================
+// Generic
+// ============================================================================
+
+const GENERIC_TITLE_KEYS: [&str; 5] = ["title", "message", "text", "summary", "name"];
+const GENERIC_PROJECT_KEYS: [&str; 3] = ["project", "repo", "repository"];
+const GENERIC_LINK_KEYS: [&str; 4] = ["link", "url", "html_url", "web_url"];
+const GENERIC_PRIORITY_KEYS: [&str; 3] = ["priority", "severity", "level"];
+
+/// Maps an arbitrary JSON body. Query parameters `title`, `project`, `link`
+/// and `priority` may hold JSON pointers (e.g. `/alert/name`); otherwise a
+/// few common top-level keys are tried.
+pub fn from_generic(query: &HashMap<String, String>, body: &Value) -> Option<NotifyPayload> {
+    let lookup = |field: &str, keys: &[&str]| -> Option<String> {
+        if let Some(pointer) = query.get(field) {
+            return string_at(body, pointer);
+        }
+        keys.iter()
+            .find_map(|key| string_at(body, &format!("/{}", key)))
+    };
+
+    let title = lookup("title", &GENERIC_TITLE_KEYS)?;
+    let priority = lookup("priority", &GENERIC_PRIORITY_KEYS)
+        .map(|p| parse_priority(&p))
+        .unwrap_or_default();
+
+    Some(payload(
+        title,
+        lookup("project", &GENERIC_PROJECT_KEYS),
+        lookup("link", &GENERIC_LINK_KEYS),
+        priority,
+    ))
+}
+
+/// Accepts our own names plus common severity vocabularies
+fn parse_priority(value: &str) -> Priority {
+    match value.to_ascii_lowercase().as_str() {
+        "low" | "info" | "debug" | "minor" => Priority::Low,
+        "high" | "warning" | "warn" | "error" | "major" => Priority::High,
+        "urgent" | "critical" | "fatal" | "emergency" | "blocker" => Priority::Urgent,
+        _ => Priority::Normal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> Value {
+        let path = format!(
+            "{}/tests/fixtures/webhooks/{}.json",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        );
+        let content = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+        serde_json::from_str(&content).unwrap()
+    }
+
+    fn query(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn github_failed_workflow_run() {
+        let payload = from_github("workflow_run", &fixture("github_workflow_run")).unwrap();
+        assert_eq!(payload.title, "CI failure on feature/tray-grouping");
+        assert_eq!(payload.project.as_deref(), Some("lovnotifier"));
+        assert_eq!(
+            payload.link.as_deref(),
+            Some("https://github.com/lovstudio/lovnotifier/actions/runs/9021734561")
+        );
+        assert_eq!(payload.priority, Priority::High);
+    }
+
+    #[test]
+    fn github_workflow_run_in_progress_is_ignored() {
+        let body = fixture("github_workflow_run_in_progress");
+        assert!(from_github("workflow_run", &body).is_none());
+    }
+
+    #[test]
+    fn github_changes_requested_review() {
+        let payload = from_github(
+            "pull_request_review",
+            &fixture("github_pull_request_review"),
+        )
+        .unwrap();
+        assert_eq!(
+            payload.title,
+            "octocat requested changes on #87 Group tray items by project"
+        );
+        assert_eq!(payload.project.as_deref(), Some("lovnotifier"));
+        assert_eq!(
+            payload.link.as_deref(),
+            Some("https://github.com/lovstudio/lovnotifier/pull/87#pullrequestreview-2051887342")
+        );
+        assert_eq!(payload.priority, Priority::High);
+    }
+
+    #[test]
+    fn github_dismissed_review_is_ignored() {
+        let body = fixture("github_pull_request_review_dismissed");
+        assert!(from_github("pull_request_review", &body).is_none());
+    }
+
+    #[test]
+    fn gitea_rejected_review() {
+        let payload = from_github(
+            "pull_request_rejected",
+            &fixture("gitea_pull_request_rejected"),
+        )
+        .unwrap();
+        assert_eq!(
+            payload.title,
+            "gitea-admin requested changes on #12 Add arm64 runner"
+        );
+        assert_eq!(payload.project.as_deref(), Some("infra"));
+        assert_eq!(
+            payload.link.as_deref(),
+            Some("http://localhost:3000/homelab/infra/pulls/12")
+        );
+        assert_eq!(payload.priority, Priority::High);
+    }
+
+    #[test]
+    fn gitea_approved_review() {
+        let mut body = fixture("gitea_pull_request_rejected");
+        body["review"]["type"] = "pull_request_review_approved".into();
+        let payload = from_github("pull_request_approved", &body).unwrap();
+        assert_eq!(payload.title, "gitea-admin approved #12 Add arm64 runner");
+        assert_eq!(payload.priority, Priority::Normal);
+    }
+
+    #[test]
+    fn gitea_review_request_is_ignored() {
+        let mut body = fixture("gitea_pull_request_rejected");
+        body["action"] = "review_requested".into();
+        assert!(from_github("pull_request_comment", &body).is_none());
+    }
+
+    #[test]
+    fn github_unknown_event_is_ignored() {
+        assert!(from_github("push", &fixture("github_workflow_run")).is_none());
+    }
+
+    #[test]
+    fn gitlab_failed_pipeline() {
+        let payload = from_gitlab(&fixture("gitlab_pipeline")).unwrap();
+        assert_eq!(payload.title, "Pipeline failed on main");
+        assert_eq!(payload.project.as_deref(), Some("deploy-bot"));
+        assert_eq!(
+            payload.link.as_deref(),
+            Some("https://gitlab.example.com/tools/deploy-bot/-/pipelines/1287345")
+        );
+        assert_eq!(payload.priority, Priority::High);
+    }
+
+    #[test]
+    fn gitlab_pipeline_link_falls_back_to_project_url() {
+        let mut body = fixture("gitlab_pipeline");
+        body["object_attributes"]
+            .as_object_mut()
+            .unwrap()
+            .remove("url");
+        let payload = from_gitlab(&body).unwrap();
+        assert_eq!(
+            payload.link.as_deref(),
+            Some("https://gitlab.example.com/tools/deploy-bot/-/pipelines/1287345")
+        );
+    }
+
+    #[test]
+    fn gitlab_running_pipeline_is_ignored() {
+        assert!(from_gitlab(&fixture("gitlab_pipeline_running")).is_none());
+    }
+
+    #[test]
+    fn gitlab_approved_merge_request() {
+        let payload = from_gitlab(&fixture("gitlab_merge_request")).unwrap();
+        assert_eq!(payload.title, "grace approved !42 Retry flaky deploy step");
+        assert_eq!(payload.project.as_deref(), Some("deploy-bot"));
+        assert_eq!(
+            payload.link.as_deref(),
+            Some("https://gitlab.example.com/tools/deploy-bot/-/merge_requests/42")
+        );
+        assert_eq!(payload.priority, Priority::Normal);
+    }
+
+    #[test]
+    fn gitlab_merge_request_update_is_ignored() {
+        assert!(from_gitlab(&fixture("gitlab_merge_request_update")).is_none());
+    }
+
+    #[test]
+    fn gitlab_merge_request_note() {
+        let payload = from_gitlab(&fixture("gitlab_note")).unwrap();
+        assert_eq!(
+            payload.title,
+            "grace commented on !42 Retry flaky deploy step"
+        );
+        assert_eq!(payload.project.as_deref(), Some("deploy-bot"));
+        assert_eq!(
+            payload.link.as_deref(),
+            Some("https://gitlab.example.com/tools/deploy-bot/-/merge_requests/42#note_1744")
+        );
+        assert_eq!(payload.priority, Priority::Low);
+    }
+
+    #[test]
+    fn gitlab_issue_note_is_ignored() {
+        assert!(from_gitlab(&fixture("gitlab_note_issue")).is_none());
+    }
+
+    #[test]
+    fn generic_top_level_keys() {
+        let payload = from_generic(&HashMap::new(), &fixture("generic_simple")).unwrap();
+        assert_eq!(payload.title, "Nightly backup finished");
+        assert_eq!(payload.project.as_deref(), Some("homelab"));
+        assert_eq!(
+            payload.link.as_deref(),
+            Some("http://backup.local/runs/2024-05-10")
+        );
+        assert_eq!(payload.priority, Priority::High);
+    }
+
+    #[test]
+    fn generic_json_pointers() {
+        let query = query(&[
+            ("title", "/alerts/0/annotations/summary"),
+            ("project", "/alerts/0/labels/instance"),
+            ("link", "/externalURL"),
+            ("priority", "/commonLabels/severity"),
+        ]);
+        let payload = from_generic(&query, &fixture("generic_alertmanager")).unwrap();
+        assert_eq!(payload.title, "Disk on build-01 is 95% full");
+        assert_eq!(payload.project.as_deref(), Some("build-01:9100"));
+        assert_eq!(
+            payload.link.as_deref(),
+            Some("http://alertmanager.local:9093")
+        );
+        assert_eq!(payload.priority, Priority::Urgent);
+    }
+
+    #[test]
+    fn generic_without_title_is_ignored() {
+        let body = fixture("generic_alertmanager");
+        assert!(from_generic(&HashMap::new(), &body).is_none());
+        let query = query(&[("title", "/alerts/0/annotations/description")]);
+        assert!(from_generic(&query, &body).is_none());
+    }
+}
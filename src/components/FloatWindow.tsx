@@ -67,23 +67,43 @@ export interface ReviewItem {
   title: string;
   project?: string;
   timestamp: number;
+  mux_kind?: "tmux" | "zellij" | "screen";
   tmux_session?: string;
   tmux_window?: string;
   tmux_pane?: string;
+  tmux_pane_id?: string;
+  tmux_socket?: string;
+  host?: string;
+  ssh_target?: string;
   session_id?: string;
   project_path?: string;
+  source?: string;
+  link?: string;
+  priority?: "low" | "normal" | "high" | "urgent";
+  tags?: string[];
+  expires_at?: number;
+  queue?: string;
+  nav_error?: string;
+  stale_since?: number;
+  snapshot?: string;
+  outcome?: "navigated" | "dismissed" | "expired" | "auto_dismissed" | "seen_in_terminal" | "stale";
 }
 
 // ============================================================================
 // FloatWindow Component
 // ============================================================================
 
+interface FocusSettings {
+  enabled: boolean;
+  projects: string[];
+  sessions: string[];
+}
+
 const SNAP_THRESHOLD = 240;
 const STORAGE_KEY = "lovnotifier-float-window";
 
+// Position is saved per monitor setup by the backend
 interface FloatWindowState {
-  x: number;
-  y: number;
   isExpanded: boolean;
   snapSide: "left" | "right" | null;
   expandDirection: "left" | "right";
@@ -119,6 +139,12 @@ export function FloatWindow() {
   const [snapSide, setSnapSide] = useState<"left" | "right" | null>(savedState.snapSide ?? null);
   const [hoveredId, setHoveredId] = useState<string | null>(null);
   const [shake, setShake] = useState(false);
+  // Item picked with the keyboard or by the cycle shortcut
+  const [selectedId, setSelectedId] = useState<string | null>(null);
+  const [dnd, setDnd] = useState(false);
+  // Projects and sessions focus mode limits the queue to, if it is on
+  const [focusNames, setFocusNames] = useState<string[]>([]);
+  const [pickerRequest, setPickerRequest] = useState(0);
   const isDraggingRef = useRef(false);
   const initializedRef = useRef(false);
   const listRef = useRef<HTMLDivElement>(null);
@@ -350,9 +376,6 @@ export function FloatWindow() {
 
     if (snappedSide !== null) {
       await win.setPosition(new LogicalPosition(newX, windowY));
-      saveState({ x: newX, y: windowY });
-    } else {
-      saveState({ x: windowX, y: windowY });
     }
   };
 
@@ -405,6 +428,44 @@ export function FloatWindow() {
     return () => { unlisten.then(fn => fn()); };
   }, []);
 
+  // Do-not-disturb state, shown on the badge and in the status bar
+  useEffect(() => {
+    invoke<boolean>("get_dnd_active").then(setDnd).catch(console.error);
+
+    const unlisten = listen<boolean>("dnd-changed", (event) => {
+      setDnd(event.payload);
+    });
+    return () => { unlisten.then(fn => fn()); };
+  }, []);
+
+  // Focus mode; the backend already filters the queue it sends
+  useEffect(() => {
+    const applyFocus = (settings: { focus: FocusSettings }) => {
+      const { enabled, projects, sessions } = settings.focus;
+      setFocusNames(enabled ? [...projects, ...sessions] : []);
+    };
+    invoke<{ focus: FocusSettings }>("get_settings").then(applyFocus).catch(console.error);
+
+    const unlisten = listen<{ focus: FocusSettings }>("settings-changed", (event) => {
+      applyFocus(event.payload);
+    });
+    return () => { unlisten.then(fn => fn()); };
+  }, []);
+
+  // Global shortcuts: quick picker and cycling through pending items
+  useEffect(() => {
+    const unlistenPicker = listen("open-quick-picker", () => {
+      setPickerRequest((n) => n + 1);
+    });
+    const unlistenSelected = listen<string>("review-item-selected", (event) => {
+      setSelectedId(event.payload);
+    });
+    return () => {
+      unlistenPicker.then(fn => fn());
+      unlistenSelected.then(fn => fn());
+    };
+  }, []);
+
   // Load completed when filter changes
   useEffect(() => {
     if (!showOnlyPending) {
@@ -443,30 +504,20 @@ export function FloatWindow() {
       } else {
         await win.setSize(new LogicalSize(getCollapsedWidth(), 48));
       }
-
-      if (saved.x !== undefined && saved.y !== undefined) {
-        await win.setPosition(new LogicalPosition(saved.x, saved.y));
-      }
     };
     initWindow();
   }, []);
 
   const handleItemClick = async (item: ReviewItem) => {
-    const hasTmuxContext = item.tmux_session && item.tmux_window && item.tmux_pane;
-
-    if (hasTmuxContext) {
-      try {
-        await invoke("navigate_to_tmux_pane", {
-          session: item.tmux_session,
-          window: item.tmux_window,
-          pane: item.tmux_pane,
-        });
-      } catch (e) {
-        console.error("navigate_to_tmux_pane failed:", e);
-      }
+    try {
+      await invoke("open_review_item", { id: item.id });
+    } catch (e) {
+      // The item stays pending and shows why, so it can be retried or
+      // dismissed explicitly
+      console.warn("open_review_item failed:", e);
+      setShake(true);
+      setTimeout(() => setShake(false), 300);
     }
-
-    handleDismiss(item.id);
   };
 
   const consumeOldestMessage = useCallback(() => {
@@ -476,6 +527,107 @@ export function FloatWindow() {
     }
   }, [items]);
 
+  const setExpanded = async (expand: boolean) => {
+    const win = getCurrentWindow();
+    const pos = await win.outerPosition();
+
+    const expandedWidth = 280;
+    const expandedHeight = 320;
+    const collapsedHeight = 48;
+    const collapsedWidth = getCollapsedWidth();
+
+    const scale = window.devicePixelRatio;
+    const windowX = pos.x / scale;
+    const windowY = pos.y / scale;
+
+    if (expand) {
+      const screenLeft = (window.screen as { availLeft?: number }).availLeft ?? 0;
+      const screenTop = (window.screen as { availTop?: number }).availTop ?? 0;
+      const screenWidth = window.screen.availWidth;
+      const screenHeight = window.screen.availHeight;
+
+      let newX = windowX;
+      let newY = windowY;
+      let newExpandDirection: "left" | "right" = "right";
+
+      if (windowX + expandedWidth > screenLeft + screenWidth) {
+        newExpandDirection = "left";
+        newX = windowX - (expandedWidth - collapsedWidth);
+        newX = Math.max(screenLeft, newX);
+      }
+
+      if (windowY + expandedHeight > screenTop + screenHeight) {
+        newY = screenTop + screenHeight - expandedHeight;
+        newY = Math.max(screenTop, newY);
+      }
+
+      setExpandDirection(newExpandDirection);
+      if (newX !== windowX || newY !== windowY) {
+        await win.setPosition(new LogicalPosition(newX, newY));
+      }
+      await win.setSize(new LogicalSize(expandedWidth, expandedHeight));
+      setIsExpanded(true);
+      saveState({ isExpanded: true, expandDirection: newExpandDirection });
+    } else {
+      setIsExpanded(false);
+      let newX = windowX;
+      if (expandDirection === "left") {
+        newX = windowX + (expandedWidth - collapsedWidth);
+        await win.setPosition(new LogicalPosition(newX, windowY));
+      }
+      await win.setSize(new LogicalSize(collapsedWidth, collapsedHeight));
+      saveState({ isExpanded: false });
+    }
+  };
+
+  // Open the pending list with the oldest item selected
+  useEffect(() => {
+    if (pickerRequest === 0) return;
+    setShowOnlyPending(true);
+    if (!isExpanded) {
+      setExpanded(true);
+    }
+    const oldest = [...items].sort((a, b) => a.timestamp - b.timestamp)[0];
+    setSelectedId(oldest?.id ?? null);
+  }, [pickerRequest]);
+
+  // Keyboard picking: arrows or j/k move, Enter opens, 1-9 open the nth item,
+  // Delete dismisses and Escape closes the list
+  useEffect(() => {
+    const handleKeyDown = (e: KeyboardEvent) => {
+      if (!isExpanded || !showOnlyPending || items.length === 0) return;
+      const index = items.findIndex((item) => item.id === selectedId);
+      if (e.key === "ArrowDown" || e.key === "j") {
+        setSelectedId(items[Math.min(index + 1, items.length - 1)].id);
+      } else if (e.key === "ArrowUp" || e.key === "k") {
+        setSelectedId(items[Math.max(index - 1, 0)].id);
+      } else if (e.key === "Enter" && index >= 0) {
+        handleItemClick(items[index]);
+      } else if ((e.key === "Delete" || e.key === "Backspace") && index >= 0) {
+        handleDismiss(items[index].id);
+      } else if (/^[1-9]$/.test(e.key) && Number(e.key) <= items.length) {
+        handleItemClick(items[Number(e.key) - 1]);
+      } else if (e.key === "Escape") {
+        setSelectedId(null);
+        setExpanded(false);
+      } else {
+        return;
+      }
+      e.preventDefault();
+    };
+
+    window.addEventListener("keydown", handleKeyDown);
+    return () => window.removeEventListener("keydown", handleKeyDown);
+  }, [isExpanded, showOnlyPending, items, selectedId]);
+
+  // Keep the selected item in view
+  useEffect(() => {
+    const index = displayItems.findIndex((item) => item.id === selectedId);
+    if (index >= 0) {
+      virtualizer.scrollToIndex(index);
+    }
+  }, [selectedId]);
+
   const handleMouseDown = (e: React.MouseEvent) => {
     const isRightClick = e.button === 2;
     const startX = e.clientX;
@@ -511,56 +663,7 @@ export function FloatWindow() {
           }
           return;
         }
-        const win = getCurrentWindow();
-        const pos = await win.outerPosition();
-
-        const expandedWidth = 280;
-        const expandedHeight = 320;
-        const collapsedHeight = 48;
-        const collapsedWidth = getCollapsedWidth();
-
-        const scale = window.devicePixelRatio;
-        const windowX = pos.x / scale;
-        const windowY = pos.y / scale;
-
-        if (!isExpanded) {
-          const screenLeft = (window.screen as { availLeft?: number }).availLeft ?? 0;
-          const screenTop = (window.screen as { availTop?: number }).availTop ?? 0;
-          const screenWidth = window.screen.availWidth;
-          const screenHeight = window.screen.availHeight;
-
-          let newX = windowX;
-          let newY = windowY;
-          let newExpandDirection: "left" | "right" = "right";
-
-          if (windowX + expandedWidth > screenLeft + screenWidth) {
-            newExpandDirection = "left";
-            newX = windowX - (expandedWidth - collapsedWidth);
-            newX = Math.max(screenLeft, newX);
-          }
-
-          if (windowY + expandedHeight > screenTop + screenHeight) {
-            newY = screenTop + screenHeight - expandedHeight;
-            newY = Math.max(screenTop, newY);
-          }
-
-          setExpandDirection(newExpandDirection);
-          if (newX !== windowX || newY !== windowY) {
-            await win.setPosition(new LogicalPosition(newX, newY));
-          }
-          await win.setSize(new LogicalSize(expandedWidth, expandedHeight));
-          setIsExpanded(true);
-          saveState({ isExpanded: true, expandDirection: newExpandDirection, x: newX, y: newY });
-        } else {
-          setIsExpanded(false);
-          let newX = windowX;
-          if (expandDirection === "left") {
-            newX = windowX + (expandedWidth - collapsedWidth);
-            await win.setPosition(new LogicalPosition(newX, windowY));
-          }
-          await win.setSize(new LogicalSize(collapsedWidth, collapsedHeight));
-          saveState({ isExpanded: false, x: newX, y: windowY });
-        }
+        await setExpanded(!isExpanded);
       }
     };
 
@@ -649,7 +752,8 @@ export function FloatWindow() {
           </motion.div>
         ) : (
           <motion.span
-            className="w-6 h-6 flex items-center justify-center text-xs font-bold bg-primary-foreground/20 rounded-full"
+            className={`w-6 h-6 flex items-center justify-center text-xs font-bold bg-primary-foreground/20 rounded-full ${dnd ? "opacity-50" : ""}`}
+            title={dnd ? "Do Not Disturb" : undefined}
             animate={shake ? { x: [-2, 2, -2, 2, 0] } : {}}
             transition={{ duration: 0.3 }}
           >
@@ -670,10 +774,12 @@ export function FloatWindow() {
           >
             {/* Status bar */}
             <div className="flex items-center gap-1 mb-2 text-xs opacity-80">
-              <span>
+              <span className="truncate">
                 {showOnlyPending
                   ? `${items.length} pending`
                   : `${items.length} pending · ${completedItems.length}${completedHasMore ? "+" : ""} done`}
+                {focusNames.length > 0 && ` · Focus: ${focusNames.join(", ")}`}
+                {dnd && " · Do Not Disturb"}
               </span>
               {!showOnlyPending && completedItems.length > 0 && (
                 <button
@@ -703,7 +809,7 @@ export function FloatWindow() {
                 >
                   {virtualizer.getVirtualItems().map((virtualRow) => {
                     const item = displayItems[virtualRow.index];
-                    const isHovered = hoveredId === item.id;
+                    const isHovered = hoveredId === item.id || selectedId === item.id;
                     const isCompleted = completedItems.some(c => c.id === item.id);
                     return (
                       <div
@@ -721,6 +827,7 @@ export function FloatWindow() {
                       >
                         <div
                           onClick={() => !isCompleted && handleItemClick(item)}
+                          title={item.snapshot}
                           className={`flex items-center gap-2 p-2 rounded-lg transition-colors ${
                             isCompleted ? "cursor-default" : "cursor-pointer"
                           } ${isHovered ? "bg-primary-foreground/20" : "bg-primary-foreground/10"} ${
@@ -738,6 +845,10 @@ export function FloatWindow() {
                             </p>
                             <p className="text-xs opacity-70 truncate">
                               #{item.seq} · {formatTime(item.timestamp)}
+                              {item.stale_since && !isCompleted
+                                ? " · ⊘ tmux pane gone"
+                                : item.nav_error && !isCompleted && ` · ⚠ ${item.nav_error}`}
+                              {isCompleted && item.outcome === "seen_in_terminal" && " · seen in terminal"}
                             </p>
                           </div>
                           {!isCompleted && (
@@ -1,5 +1,6 @@
 import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 
 interface NotifierSettings {
   notify: boolean;
@@ -16,18 +17,36 @@ export function Settings() {
     shortcut: "F4",
   });
   const [saved, setSaved] = useState(false);
+  const [error, setError] = useState<string | null>(null);
 
   useEffect(() => {
     invoke<NotifierSettings>("get_settings").then(setSettings).catch(console.error);
+
+    const unlisten = listen<NotifierSettings>("settings-changed", (event) => {
+      setSettings(event.payload);
+    });
+    return () => { unlisten.then(fn => fn()); };
   }, []);
 
   const handleSave = async () => {
     try {
       await invoke("save_settings", { settings });
+      setError(null);
       setSaved(true);
       setTimeout(() => setSaved(false), 2000);
     } catch (e) {
       console.error("Failed to save settings:", e);
+      setError(String(e));
+    }
+  };
+
+  const resetFloatPosition = async () => {
+    try {
+      await invoke("reset_float_window_position");
+      setError(null);
+    } catch (e) {
+      console.error("Failed to reset float window position:", e);
+      setError(String(e));
     }
   };
 
@@ -62,23 +81,51 @@ export function Settings() {
             <span className="text-foreground">Enable system notifications</span>
           </label>
 
+          <div className="flex items-center">
+            <label className="flex items-center gap-3 cursor-pointer group">
+              <button
+                type="button"
+                role="switch"
+                aria-checked={settings.float_window}
+                onClick={() => toggle("float_window")}
+                className={`relative w-11 h-6 rounded-full transition-colors ${
+                  settings.float_window ? "bg-primary" : "bg-muted"
+                }`}
+              >
+                <span
+                  className={`absolute top-0.5 left-0.5 w-5 h-5 bg-card rounded-full transition-transform shadow-sm ${
+                    settings.float_window ? "translate-x-5" : "translate-x-0"
+                  }`}
+                />
+              </button>
+              <span className="text-foreground">Show float window</span>
+            </label>
+            <button
+              type="button"
+              onClick={resetFloatPosition}
+              className="ml-auto text-sm text-muted-foreground hover:text-foreground transition-colors"
+            >
+              Reset position
+            </button>
+          </div>
+
           <label className="flex items-center gap-3 cursor-pointer group">
             <button
               type="button"
               role="switch"
-              aria-checked={settings.float_window}
-              onClick={() => toggle("float_window")}
+              aria-checked={settings.menu_bar}
+              onClick={() => toggle("menu_bar")}
               className={`relative w-11 h-6 rounded-full transition-colors ${
-                settings.float_window ? "bg-primary" : "bg-muted"
+                settings.menu_bar ? "bg-primary" : "bg-muted"
               }`}
             >
               <span
                 className={`absolute top-0.5 left-0.5 w-5 h-5 bg-card rounded-full transition-transform shadow-sm ${
-                  settings.float_window ? "translate-x-5" : "translate-x-0"
+                  settings.menu_bar ? "translate-x-5" : "translate-x-0"
                 }`}
               />
             </button>
-            <span className="text-foreground">Show float window</span>
+            <span className="text-foreground">Show menu bar icon</span>
           </label>
 
           <div className="flex items-center gap-3">
@@ -86,12 +133,15 @@ export function Settings() {
             <input
               type="text"
               value={settings.shortcut}
+              placeholder="CmdOrCtrl+Shift+F4"
               onChange={(e) => setSettings((s) => ({ ...s, shortcut: e.target.value }))}
-              className="border border-input bg-background rounded-lg px-3 py-1.5 w-20 text-foreground focus:outline-none focus:ring-2 focus:ring-ring"
+              className="border border-input bg-background rounded-lg px-3 py-1.5 w-48 text-foreground focus:outline-none focus:ring-2 focus:ring-ring"
             />
           </div>
         </div>
 
+        {error && <p className="mt-4 text-sm text-destructive">{error}</p>}
+
         <button
           onClick={handleSave}
           className="mt-6 px-5 py-2.5 bg-primary text-primary-foreground rounded-xl hover:bg-primary/90 transition-colors font-medium"